@@ -12,6 +12,9 @@ fn main() {
     let binaries_dir = manifest_dir.join("binaries");
     fs::create_dir_all(&binaries_dir).expect("Failed to create binaries directory");
 
+    // Emoji font for captions and text overlays (bundled with the other fonts)
+    ensure_emoji_font(&manifest_dir.join("fonts"));
+
     // Platform-specific ffmpeg binary names and URLs
     let (ffmpeg_name, download_url) = match (target_os.as_str(), target_arch.as_str()) {
         ("windows", "x86_64") => (
@@ -74,6 +77,45 @@ fn main() {
     tauri_build::build();
 }
 
+// Outline Noto Emoji (libass can't render the bitmap glyphs of Noto Color Emoji), saved under the
+// file name the exporter looks for (EMOJI_FONT_FILE in src/clips/subtitle.rs), with its license
+const NOTO_EMOJI_BASE_URL: &str = "https://raw.githubusercontent.com/google/fonts/main/ofl/notoemoji";
+const EMOJI_FONT_ASSETS: [(&str, &str); 2] = [
+    ("NotoEmoji%5Bwght%5D.ttf", "NotoEmoji-Regular.ttf"),
+    ("OFL.txt", "OFL-NotoEmoji.txt"),
+];
+
+fn ensure_emoji_font(fonts_dir: &Path) {
+    for (remote_name, file_name) in EMOJI_FONT_ASSETS {
+        let path = fonts_dir.join(file_name);
+        if path.exists() {
+            continue;
+        }
+        let url = format!("{}/{}", NOTO_EMOJI_BASE_URL, remote_name);
+        println!("cargo:warning=Downloading {}...", file_name);
+        match download_file(&url, &path) {
+            Ok(_) => println!("cargo:warning=Successfully downloaded {} to {:?}", file_name, path),
+            Err(e) => {
+                println!("cargo:warning=Failed to download {}: {}", file_name, e);
+                println!("cargo:warning=Please download manually from: {}", url);
+                println!("cargo:warning=Place at: {:?}", path);
+            }
+        }
+    }
+}
+
+// Download a file as is; written under a temporary name so a failed download leaves nothing behind
+fn download_file(url: &str, output_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let response = ureq::get(url).call()?;
+    let mut bytes = Vec::new();
+    response.into_reader().read_to_end(&mut bytes)?;
+
+    let partial_path = output_path.with_extension("part");
+    fs::write(&partial_path, &bytes)?;
+    fs::rename(&partial_path, output_path)?;
+    Ok(())
+}
+
 fn download_and_extract_ffmpeg(
     url: &str,
     output_path: &Path,
@@ -0,0 +1,9 @@
+-- Move legacy subtitle animation values to "pop"
+-- Migration: 034_migrate_legacy_animation_styles
+-- Created: 2026-10-18
+
+-- "none", "fade" and "word-by-word" all exported with the pop animation. "none" now means
+-- no animation, so existing presets keep their look under the explicit value.
+UPDATE custom_subtitle_presets
+SET animation_style = 'pop'
+WHERE animation_style IN ('none', 'fade', 'word-by-word');
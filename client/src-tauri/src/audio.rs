@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 use crate::storage;
 use crate::ffmpeg_utils::{parse_duration_from_ffmpeg_output};
+use crate::video_server::VIDEO_SERVER_PORT;
+use crate::clips::SpeechEnhancement;
+use crate::clips::speech_enhancement::speech_enhancement_filter;
+
+// Extracted audio stays on disk until the frontend releases it; files older than this are
+// removed at startup (left behind by crashes or abandoned uploads)
+const STALE_AUDIO_SECS: u64 = 24 * 60 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioChunk {
@@ -10,53 +17,153 @@ pub struct AudioChunk {
     pub start_time: f64,
     pub end_time: f64,
     pub duration: f64,
-    pub base64_data: String,
+    // Local video server URL the file can be streamed from
+    pub url: String,
+    pub file_size: u64,
+    // Seconds shared with the previous/next chunk. Zero where the chunks were split in a
+    // silence: start_time/end_time are then exact and the transcripts need no deduplication.
+    #[serde(default)]
+    pub overlap_before: f64,
+    #[serde(default)]
+    pub overlap_after: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedAudio {
+    pub file_path: String,
+    pub filename: String,
+    pub url: String,
     pub file_size: u64,
 }
 
+// Directory holding extracted audio files
+fn audio_dir() -> Result<std::path::PathBuf, String> {
+    let paths = storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let dir = paths.temp.join("audio");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create audio directory: {}", e))?;
+    Ok(dir)
+}
+
+// URL of a file on the local video server
+fn local_file_url(path: &std::path::Path) -> String {
+    use base64::{Engine as _, engine::general_purpose};
+    let encoded = general_purpose::STANDARD.encode(path.to_string_lossy().as_bytes());
+    format!("http://localhost:{}/video/{}", VIDEO_SERVER_PORT, encoded)
+}
+
+fn file_size(path: &std::path::Path) -> Result<u64, String> {
+    std::fs::metadata(path)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read audio file metadata: {}", e))
+}
+
+// Output arguments shared by the extraction commands: optional speech enhancement, then OGG Vorbis
+fn vorbis_output_args(audio_filter: Option<&str>, output_path: &std::path::Path) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    if let Some(filter) = audio_filter {
+        args.extend_from_slice(&["-af".to_string(), filter.to_string()]);
+    }
+    args.extend_from_slice(&[
+        "-c:a".to_string(), "libvorbis".to_string(),  // OGG Vorbis codec (better compression)
+        "-q:a".to_string(), "1".to_string(),          // Quality level 1 (~64-96k MP3 equivalent, optimal for transcription)
+        "-vn".to_string(),                            // No video
+        "-y".to_string(),                             // Overwrite output file
+        output_path.to_str().ok_or("Invalid audio output path")?.to_string(),
+    ]);
+    Ok(args)
+}
+
+// Remove extracted audio files left over from earlier sessions
+pub fn cleanup_stale_audio_files() {
+    let Ok(dir) = audio_dir() else {
+        return;
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return;
+    };
+    let mut removed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let age = entry.metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        if age > STALE_AUDIO_SECS && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        println!("[Rust] Removed {} stale audio files", removed);
+    }
+}
+
+// Delete extracted audio files once the frontend is done with them (uploads finished).
+// Only files inside the audio directory are touched.
+#[tauri::command]
+pub async fn release_audio_files(file_paths: Vec<String>) -> Result<u32, String> {
+    let dir = audio_dir()?;
+    let dir = dir.canonicalize().unwrap_or(dir);
+    let mut removed = 0;
+    for file_path in file_paths {
+        let path = std::path::PathBuf::from(&file_path);
+        let Ok(path) = path.canonicalize() else {
+            continue;
+        };
+        if !path.starts_with(&dir) {
+            println!("[Rust] Not releasing file outside the audio directory: {}", file_path);
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(_) => removed += 1,
+            Err(e) => eprintln!("[Rust] Warning: Failed to remove audio file {}: {}", path.display(), e),
+        }
+    }
+    println!("[Rust] Released {} audio files", removed);
+    Ok(removed)
+}
+
 #[tauri::command]
 pub async fn extract_audio_from_video(
     app: tauri::AppHandle,
     video_path: String,
-    output_path: String
-) -> Result<(String, String), String> {
+    output_path: String,
+    speech_enhancement: Option<SpeechEnhancement>
+) -> Result<ExtractedAudio, String> {
     use tauri_plugin_shell::ShellExt;
 
     println!("[Rust] extract_audio_from_video called with:");
     println!("[Rust]   video_path: {}", video_path);
     println!("[Rust]   output_path: {}", output_path);
 
-    // Get storage paths for temporary file
-    let paths = storage::init_storage_dirs()
-        .map_err(|e| {
-            println!("[Rust] Failed to get storage paths: {}", e);
-            format!("Failed to get storage paths: {}", e)
-        })?;
-
-    // Create a unique temporary audio file path - OGG format for better compression
-    let temp_audio_path = paths.videos.join(format!("temp_audio_{}_audio_only.ogg",
+    // Unique file in the audio directory, named after the requested output - OGG format for better compression
+    let requested_name = std::path::Path::new(&output_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio".to_string());
+    let temp_audio_path = audio_dir()?.join(format!("{}_{}.ogg",
+        requested_name,
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map_err(|e| format!("Failed to get timestamp: {}", e))?
-            .as_secs()
+            .as_millis()
     ));
 
-    println!("[Rust] Temporary audio path: {}", temp_audio_path.display());
+    println!("[Rust] Audio path: {}", temp_audio_path.display());
 
     // Use FFmpeg to extract audio as OGG Vorbis - optimized for transcription
     let shell = app.shell();
     println!("[Rust] Running FFmpeg to extract audio...");
 
+    let audio_filter = speech_enhancement.as_ref().and_then(speech_enhancement_filter);
+    let mut args = vec!["-i".to_string(), video_path.clone()];
+    args.extend(vorbis_output_args(audio_filter.as_deref(), &temp_audio_path)?);
+
     let output = shell.sidecar("ffmpeg")
         .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-        .args([
-            "-i", &video_path,
-            "-c:a", "libvorbis",  // OGG Vorbis codec (better compression)
-            "-q:a", "1",          // Quality level 1 (~64-96k MP3 equivalent, optimal for transcription)
-            "-vn",               // No video
-            "-y",                // Overwrite output file
-            temp_audio_path.to_str().ok_or("Invalid temporary audio path")?,
-        ])
+        .args(args)
         .output()
         .await
         .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
@@ -78,35 +185,178 @@ pub async fn extract_audio_from_video(
 
     println!("[Rust] FFmpeg extraction completed successfully");
 
-    // Read the MP3 file and return as base64 encoded data
-    println!("[Rust] Reading MP3 file for base64 encoding...");
-    let audio_bytes = std::fs::read(&temp_audio_path)
-        .map_err(|e| {
-            println!("[Rust] Failed to read temporary audio file: {}", e);
-            format!("Failed to read audio file: {}", e)
-        })?;
+    let filename = temp_audio_path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("audio.ogg")
+        .to_string();
+    let audio = ExtractedAudio {
+        file_path: temp_audio_path.to_string_lossy().to_string(),
+        filename,
+        url: local_file_url(&temp_audio_path),
+        file_size: file_size(&temp_audio_path)?,
+    };
+
+    println!("[Rust] Audio extraction completed successfully ({} bytes)", audio.file_size);
+    Ok(audio)
+}
 
-    println!("[Rust] Read {} bytes from audio file", audio_bytes.len());
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioChunkingProgress {
+    pub project_id: String,
+    pub completed_chunks: usize,
+    pub total_chunks: usize,
+    pub progress: f64,
+}
 
-    // Encode to base64
-    use base64::{Engine as _, engine::general_purpose};
-    let base64_data = general_purpose::STANDARD.encode(&audio_bytes);
-    println!("[Rust] Encoded {} bytes to {} chars of base64", audio_bytes.len(), base64_data.len());
+// Final chunks shorter than this are merged into the previous chunk
+const MIN_CHUNK_SECS: f64 = 30.0;
+// Chunk boundaries move at most this far from the target time to land in a silence
+const BOUNDARY_SEARCH_SECS: f64 = 30.0;
+// Shortest pause a chunk may be split in
+pub(crate) const MIN_SILENCE_SECS: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkBoundary {
+    pub time: f64,
+    // Placed in a silence; otherwise a fixed cut that the neighbouring chunks overlap
+    pub at_silence: bool,
+}
 
-    // Get filename for return
-    let filename = temp_audio_path.file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("audio.ogg");
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannedChunk {
+    pub start: f64,
+    pub end: f64,
+    pub overlap_before: f64,
+    pub overlap_after: f64,
+}
 
-    // Clean up the temporary file
-    if let Err(e) = std::fs::remove_file(&temp_audio_path) {
-        eprintln!("[Rust] Warning: Failed to remove temporary audio file {}: {}", temp_audio_path.display(), e);
-    } else {
-        println!("[Rust] Cleaned up temporary audio file");
+// Target boundary times, every `chunk_secs`; a final piece under MIN_CHUNK_SECS stays with the
+// previous chunk
+pub fn chunk_targets(duration: f64, chunk_secs: f64) -> Vec<f64> {
+    let chunk_secs = chunk_secs.max(MIN_CHUNK_SECS);
+    (1..)
+        .map(|k| k as f64 * chunk_secs)
+        .take_while(|&t| t < duration - MIN_CHUNK_SECS)
+        .collect()
+}
+
+// The silence closest to `target`, cut in its middle
+pub fn pick_boundary(silences: &[(f64, f64)], target: f64) -> Option<f64> {
+    silences
+        .iter()
+        .map(|&(start, end)| (start + end) / 2.0)
+        .min_by(|a, b| (a - target).abs().partial_cmp(&(b - target).abs()).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+// Chunks between consecutive boundaries. Chunks split at a fixed cut overlap by `overlap_secs`
+// (the later chunk starts early) so no words are lost where the cut lands mid-word.
+pub fn plan_chunks(duration: f64, boundaries: &[ChunkBoundary], overlap_secs: f64) -> Vec<PlannedChunk> {
+    let mut chunks: Vec<PlannedChunk> = Vec::with_capacity(boundaries.len() + 1);
+    let mut start = 0.0;
+    let mut overlap_before = 0.0;
+    for boundary in boundaries {
+        let overlap = if boundary.at_silence { 0.0 } else { overlap_secs.min(boundary.time - start) };
+        chunks.push(PlannedChunk { start, end: boundary.time, overlap_before, overlap_after: overlap });
+        start = boundary.time - overlap;
+        overlap_before = overlap;
+    }
+    chunks.push(PlannedChunk { start, end: duration, overlap_before, overlap_after: 0.0 });
+    chunks
+}
+
+// Move a boundary target into the nearest pause in the surrounding speech. Falls back to the
+// target itself when the audio around it has none (continuous speech, music).
+async fn find_chunk_boundary(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    target: f64,
+    search_secs: f64,
+    video_duration: f64
+) -> ChunkBoundary {
+    use crate::clips::alignment::{SpeechActivity, ALIGNMENT_SAMPLE_RATE};
+
+    let window_start = (target - search_secs).max(0.0);
+    // Keep the final chunk at least MIN_CHUNK_SECS long
+    let window_end = (target + search_secs).min(video_duration - MIN_CHUNK_SECS);
+    let samples = match crate::clips::read_audio_samples(app, video_path, window_start, window_end - window_start, ALIGNMENT_SAMPLE_RATE).await {
+        Ok(samples) => samples,
+        Err(e) => {
+            println!("[Rust] Could not analyse audio around {:.2}s: {}", target, e);
+            return ChunkBoundary { time: target, at_silence: false };
+        }
+    };
+    let silence = SpeechActivity::from_samples(&samples, ALIGNMENT_SAMPLE_RATE, window_start)
+        .and_then(|activity| pick_boundary(&activity.silences(MIN_SILENCE_SECS), target));
+    match silence {
+        Some(time) => ChunkBoundary { time, at_silence: true },
+        None => {
+            println!("[Rust] No silence near {:.2}s, using a fixed boundary", target);
+            ChunkBoundary { time: target, at_silence: false }
+        }
+    }
+}
+
+// Duration from the container header (no decode); falls back to decoding the file when the
+// header has none (e.g. unfinished recordings)
+async fn probe_duration(app: &tauri::AppHandle, video_path: &str) -> Result<f64, String> {
+    use tauri_plugin_shell::ShellExt;
+
+    let header_output = app.shell().sidecar("ffmpeg")
+        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
+        .args(["-i", video_path])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg for duration: {}", e))?;
+    // Exits with an error because no output is given, the header is printed anyway
+    let stderr = String::from_utf8_lossy(&header_output.stderr);
+    if let Ok(duration) = parse_duration_from_ffmpeg_output(&stderr) {
+        return Ok(duration);
     }
 
-    println!("[Rust] Audio extraction completed successfully");
-    Ok((filename.to_string(), base64_data))
+    println!("[Rust] No duration in header, decoding to measure it...");
+    let decode_output = app.shell().sidecar("ffmpeg")
+        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
+        .args(["-i", video_path, "-f", "null", "-"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg for duration: {}", e))?;
+    let stderr = String::from_utf8_lossy(&decode_output.stderr);
+    parse_duration_from_ffmpeg_output(&stderr)
+        .map_err(|e| format!("Failed to parse video duration: {}", e))
+}
+
+// Encode one chunk. Seeking on the input means only the chunk's own range is decoded.
+async fn extract_chunk(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    chunk_path: &std::path::Path,
+    start: f64,
+    duration: f64,
+    audio_filter: Option<&str>
+) -> Result<(), String> {
+    use tauri_plugin_shell::ShellExt;
+
+    let mut args = vec![
+        "-ss".to_string(), format!("{:.3}", start),
+        "-t".to_string(), format!("{:.3}", duration),
+        "-i".to_string(), video_path.to_string(),
+    ];
+    args.extend(vorbis_output_args(audio_filter, chunk_path)?);
+
+    // Vorbis encoding is single threaded: one slot of the shared FFmpeg CPU budget per chunk
+    let _slot = crate::clips::scheduler::acquire_ffmpeg_slots(1).await;
+    let output = app.shell().sidecar("ffmpeg")
+        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg extraction failed: {}", stderr));
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -115,9 +365,13 @@ pub async fn extract_and_chunk_audio(
     video_path: String,
     project_id: String,
     chunk_duration_minutes: u32,
-    overlap_seconds: u32
+    overlap_seconds: u32,
+    split_at_silence: Option<bool>,
+    speech_enhancement: Option<SpeechEnhancement>
 ) -> Result<Vec<AudioChunk>, String> {
-    use tauri_plugin_shell::ShellExt;
+    use futures::future::join_all;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tauri::Emitter;
 
     println!("[Rust] extract_and_chunk_audio called with:");
     println!("[Rust]   video_path: {}", video_path);
@@ -125,125 +379,128 @@ pub async fn extract_and_chunk_audio(
     println!("[Rust]   chunk_duration_minutes: {}", chunk_duration_minutes);
     println!("[Rust]   overlap_seconds: {}", overlap_seconds);
 
-    // Get storage paths for temporary files
-    let paths = storage::init_storage_dirs()
-        .map_err(|e| {
-            println!("[Rust] Failed to get storage paths: {}", e);
-            format!("Failed to get storage paths: {}", e)
-        })?;
-
-    let chunk_duration_secs = chunk_duration_minutes as f64 * 60.0;
-    let overlap_secs = overlap_seconds as f64;
-    let shell = app.shell();
-
-    // First, get video duration using FFmpeg
-    println!("[Rust] Getting video duration...");
-    let duration_output = shell.sidecar("ffmpeg")
-        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-        .args([
-            "-i", &video_path,
-            "-f", "null",
-            "-"
-        ])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run ffmpeg for duration: {}", e))?;
-
-    // Parse duration from FFmpeg output
-    let stderr = String::from_utf8_lossy(&duration_output.stderr);
-    let video_duration = parse_duration_from_ffmpeg_output(&stderr)
-        .map_err(|e| format!("Failed to parse video duration: {}", e))?;
+    let audio_dir = audio_dir()?;
+    let audio_filter = speech_enhancement.as_ref().and_then(speech_enhancement_filter);
 
+    let video_duration = probe_duration(&app, &video_path).await?;
     println!("[Rust] Video duration: {:.2} seconds", video_duration);
-
     if video_duration <= 0.0 {
         return Err("Invalid video duration".to_string());
     }
 
-    // Calculate number of chunks needed
-    let mut chunks = Vec::new();
-    let mut current_start = 0.0;
-    let mut chunk_index = 1;
-
-    while current_start < video_duration {
-        let current_end = (current_start + chunk_duration_secs).min(video_duration);
-        let actual_duration = current_end - current_start;
-
-        // Skip very small final chunks
-        if actual_duration < 30.0 {
-            println!("[Rust] Skipping small final chunk of {:.2} seconds", actual_duration);
-            break;
-        }
-
-        println!("[Rust] Processing chunk {}: {:.2}s - {:.2}s (duration: {:.2}s)",
-                chunk_index, current_start, current_end, actual_duration);
-
-        // Create chunk file path
-        let chunk_filename = format!("{}_chunk_{:03}.ogg", project_id, chunk_index);
-        let chunk_path = paths.videos.join(&chunk_filename);
-
-        // Extract chunk using FFmpeg
-        let chunk_output = shell.sidecar("ffmpeg")
-            .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-            .args([
-                "-i", &video_path,
-                "-ss", &format!("{:.3}", current_start),
-                "-t", &format!("{:.3}", actual_duration),
-                "-c:a", "libvorbis",  // OGG Vorbis codec (better compression)
-                "-q:a", "1",          // Quality level 1 (~64-96k MP3 equivalent, optimal for transcription)
-                "-vn",               // No video
-                "-y",                // Overwrite output file
-                chunk_path.to_str().ok_or("Invalid chunk path")?,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to run ffmpeg for chunk {}: {}", chunk_index, e))?;
-
-        if !chunk_output.status.success() {
-            let stderr = String::from_utf8_lossy(&chunk_output.stderr);
-            println!("[Rust] FFmpeg chunk {} failed: {}", chunk_index, stderr);
-            return Err(format!("FFmpeg chunk {} extraction failed: {}", chunk_index, stderr));
+    let chunk_secs = chunk_duration_minutes as f64 * 60.0;
+    let targets = chunk_targets(video_duration, chunk_secs);
+    let boundaries: Vec<ChunkBoundary> = if split_at_silence.unwrap_or(true) {
+        let search_secs = BOUNDARY_SEARCH_SECS.min(chunk_secs / 4.0);
+        join_all(targets.iter().map(|&target| find_chunk_boundary(&app, &video_path, target, search_secs, video_duration))).await
+    } else {
+        targets.iter().map(|&time| ChunkBoundary { time, at_silence: false }).collect()
+    };
+    let silent_boundaries = boundaries.iter().filter(|b| b.at_silence).count();
+    println!("[Rust] {} of {} chunk boundaries placed in silence", silent_boundaries, boundaries.len());
+
+    let plan = plan_chunks(video_duration, &boundaries, overlap_seconds as f64);
+    let total_chunks = plan.len();
+    // Stamped per run, so a second run for the project never overwrites (or has released) this run's files
+    let run_stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| format!("Failed to get timestamp: {}", e))?
+        .as_millis();
+    println!("[Rust] Extracting {} chunks in parallel", total_chunks);
+
+    let emit_progress = |completed_chunks: usize| {
+        let _ = app.emit("audio-chunking-progress", AudioChunkingProgress {
+            project_id: project_id.clone(),
+            completed_chunks,
+            total_chunks,
+            progress: completed_chunks as f64 / total_chunks.max(1) as f64 * 100.0,
+        });
+    };
+    emit_progress(0);
+
+    let completed = AtomicUsize::new(0);
+    let tasks = plan.iter().enumerate().map(|(i, planned)| {
+        let (start_time, end_time) = (planned.start, planned.end);
+        let chunk_index = i + 1;
+        let chunk_filename = format!("{}_{}_chunk_{:03}.ogg", project_id, run_stamp, chunk_index);
+        let chunk_path = audio_dir.join(&chunk_filename);
+        let (app, video_path, project_id, completed, emit_progress) = (&app, &video_path, &project_id, &completed, &emit_progress);
+        let audio_filter = audio_filter.as_deref();
+        async move {
+            let duration = end_time - start_time;
+            extract_chunk(app, video_path, &chunk_path, start_time, duration, audio_filter)
+                .await
+                .map_err(|e| format!("Chunk {} ({:.2}s - {:.2}s): {}", chunk_index, start_time, end_time, e))?;
+
+            let chunk_size = file_size(&chunk_path)?;
+            println!("[Rust] Chunk {} completed: {:.2}s - {:.2}s, {} bytes", chunk_index, start_time, end_time, chunk_size);
+            emit_progress(completed.fetch_add(1, Ordering::SeqCst) + 1);
+
+            Ok::<_, String>(AudioChunk {
+                chunk_id: format!("{}_{}", project_id, chunk_index),
+                file_path: chunk_path.to_string_lossy().to_string(),
+                filename: chunk_filename,
+                start_time,
+                end_time,
+                duration,
+                url: local_file_url(&chunk_path),
+                file_size: chunk_size,
+                overlap_before: planned.overlap_before,
+                overlap_after: planned.overlap_after,
+            })
         }
-
-        // Read chunk file and encode to base64
-        let chunk_bytes = std::fs::read(&chunk_path)
-            .map_err(|e| format!("Failed to read chunk file {}: {}", chunk_index, e))?;
-
-        let base64_data = {
-            use base64::{Engine as _, engine::general_purpose};
-            general_purpose::STANDARD.encode(&chunk_bytes)
-        };
-
-        // Create audio chunk struct
-        let audio_chunk = AudioChunk {
-            chunk_id: format!("{}_{}", project_id, chunk_index),
-            file_path: chunk_path.to_string_lossy().to_string(),
-            filename: chunk_filename,
-            start_time: current_start,
-            end_time: current_end,
-            duration: actual_duration,
-            base64_data,
-            file_size: chunk_bytes.len() as u64,
-        };
-
-        chunks.push(audio_chunk);
-        println!("[Rust] Chunk {} completed: {} bytes", chunk_index, chunk_bytes.len());
-
-        // Clean up temporary chunk file
-        if let Err(e) = std::fs::remove_file(&chunk_path) {
-            eprintln!("[Rust] Warning: Failed to remove chunk file {}: {}", chunk_path.display(), e);
+    });
+    let results = join_all(tasks).await;
+
+    let mut chunks = Vec::with_capacity(total_chunks);
+    let mut first_error = None;
+    for result in results {
+        match result {
+            Ok(chunk) => chunks.push(chunk),
+            Err(e) => {
+                println!("[Rust] {}", e);
+                first_error.get_or_insert(e);
+            }
         }
-
-        // Move to next chunk (with overlap for long videos)
-        current_start = current_end - if current_end < video_duration { overlap_secs } else { 0.0 };
-        chunk_index += 1;
-
-        // Safety check to prevent infinite loop
-        if chunk_index > 100 {
-            return Err("Too many chunks - possible infinite loop".to_string());
+    }
+    if let Some(e) = first_error {
+        // Don't leave the chunks that did succeed behind
+        for chunk in &chunks {
+            let _ = std::fs::remove_file(&chunk.file_path);
         }
+        return Err(format!("Audio chunking failed: {}", e));
     }
 
     println!("[Rust] Audio chunking completed successfully. Created {} chunks.", chunks.len());
     Ok(chunks)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_targets() {
+        assert_eq!(chunk_targets(3900.0, 1800.0), vec![1800.0, 3600.0]);
+        // A final piece under 30s joins the previous chunk
+        assert!(chunk_targets(1815.0, 1800.0).is_empty());
+        // No cap on the number of chunks
+        assert_eq!(chunk_targets(4.0 * 3600.0, 60.0).len(), 239);
+    }
+
+    #[test]
+    fn test_plan_chunks() {
+        let boundaries = [
+            ChunkBoundary { time: 1790.5, at_silence: true },
+            ChunkBoundary { time: 3600.0, at_silence: false },
+        ];
+        let plan = plan_chunks(3900.0, &boundaries, 30.0);
+        assert_eq!(plan, vec![
+            PlannedChunk { start: 0.0, end: 1790.5, overlap_before: 0.0, overlap_after: 0.0 },
+            PlannedChunk { start: 1790.5, end: 3600.0, overlap_before: 0.0, overlap_after: 30.0 },
+            PlannedChunk { start: 3570.0, end: 3900.0, overlap_before: 30.0, overlap_after: 0.0 },
+        ]);
+        assert_eq!(pick_boundary(&[(1760.0, 1761.0), (1790.0, 1791.0), (1830.0, 1840.0)], 1800.0), Some(1790.5));
+        assert_eq!(pick_boundary(&[], 1800.0), None);
+    }
+}
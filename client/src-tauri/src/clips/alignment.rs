@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+use super::types::WordInfo;
+
+// Energy-based word alignment: transcript word times drift (most near segment boundaries), so
+// each word's start/end is snapped to the nearest speech onset/offset in the clip audio.
+// A boundary only moves when a clear silence/speech transition lies within reach; words inside
+// continuous speech keep their times.
+
+// Sample rate the audio is decoded at for alignment
+pub const ALIGNMENT_SAMPLE_RATE: u32 = 16000;
+// Audio decoded around each clip segment, so boundary words can snap to speech just outside it
+pub const ALIGNMENT_MARGIN_SECS: f64 = 0.5;
+// Analysis frame length
+const FRAME_SECS: f64 = 0.01;
+// Furthest a word boundary may move
+const MAX_SHIFT_SECS: f64 = 0.25;
+// Shorter words after snapping keep their original times
+const MIN_WORD_SECS: f64 = 0.05;
+// Changes below this are not reported
+const REPORT_THRESHOLD_SECS: f64 = 0.02;
+// Audio with less contrast between quiet and loud frames has no usable speech boundaries
+const MIN_DYNAMIC_RANGE_DB: f32 = 10.0;
+// Silences up to this many frames inside speech are treated as speech (stop consonants)
+const MAX_GAP_FRAMES: usize = 3;
+
+// A word whose times were changed by the alignment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordShift {
+    // Index into the transcript words
+    pub index: usize,
+    pub word: String,
+    pub original_start: f64,
+    pub original_end: f64,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignmentReport {
+    // Words inside the analysed audio
+    pub aligned_words: usize,
+    pub moved: Vec<WordShift>,
+}
+
+// Speech/silence per analysis frame of a stretch of audio
+pub struct SpeechActivity {
+    // Source time of the first frame
+    pub start: f64,
+    frames: Vec<bool>,
+}
+
+impl SpeechActivity {
+    // Classify frames of mono 16-bit samples against a threshold between the noise floor and the
+    // speech level of this stretch of audio. None when the audio has no clear speech boundaries.
+    pub fn from_samples(samples: &[i16], sample_rate: u32, start: f64) -> Option<Self> {
+        let frame_len = ((sample_rate as f64 * FRAME_SECS) as usize).max(1);
+        let levels: Vec<f32> = samples
+            .chunks(frame_len)
+            .map(|frame| {
+                let power = frame.iter().map(|&s| (s as f64 / 32768.0).powi(2)).sum::<f64>() / frame.len() as f64;
+                (10.0 * (power + 1e-10).log10()) as f32
+            })
+            .collect();
+        if levels.len() < 10 {
+            return None;
+        }
+
+        let mut sorted = levels.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let floor = sorted[sorted.len() / 10];
+        let peak = sorted[sorted.len() * 95 / 100];
+        if peak - floor < MIN_DYNAMIC_RANGE_DB {
+            return None;
+        }
+        let threshold = floor + (peak - floor) * 0.35;
+
+        let mut frames: Vec<bool> = levels.iter().map(|&level| level >= threshold).collect();
+        // Close short gaps between speech frames
+        let mut i = 0;
+        while i < frames.len() {
+            if frames[i] {
+                i += 1;
+                continue;
+            }
+            let gap_end = frames[i..].iter().position(|&f| f).map(|p| i + p).unwrap_or(frames.len());
+            if i > 0 && gap_end < frames.len() && gap_end - i <= MAX_GAP_FRAMES {
+                frames[i..gap_end].iter_mut().for_each(|f| *f = true);
+            }
+            i = gap_end;
+        }
+        Some(Self { start, frames })
+    }
+
+    pub fn end(&self) -> f64 {
+        self.start + self.frames.len() as f64 * FRAME_SECS
+    }
+
+    // (start, end) of the pauses of at least `min_secs` between stretches of speech
+    pub fn silences(&self, min_secs: f64) -> Vec<(f64, f64)> {
+        let min_frames = (min_secs / FRAME_SECS).ceil() as usize;
+        let mut silences = Vec::new();
+        let mut i = 0;
+        while i < self.frames.len() {
+            if self.frames[i] {
+                i += 1;
+                continue;
+            }
+            let run_end = self.frames[i..].iter().position(|&f| f).map(|p| i + p).unwrap_or(self.frames.len());
+            if run_end - i >= min_frames {
+                silences.push((self.frame_start(i), self.frame_start(run_end)));
+            }
+            i = run_end;
+        }
+        silences
+    }
+
+    fn frame_at(&self, time: f64) -> Option<usize> {
+        let index = ((time - self.start) / FRAME_SECS).floor();
+        (index >= 0.0 && (index as usize) < self.frames.len()).then_some(index as usize)
+    }
+
+    fn frame_start(&self, index: usize) -> f64 {
+        self.start + index as f64 * FRAME_SECS
+    }
+
+    // Start time of the speech containing or following `time`, searched within [min, max]
+    fn onset_near(&self, time: f64, min: f64, max: f64) -> Option<f64> {
+        let index = self.frame_at(time)?;
+        if self.frames[index] {
+            // Back to where this stretch of speech begins
+            (0..=index)
+                .rev()
+                .take_while(|&i| self.frame_start(i + 1) > min)
+                .find(|&i| i == 0 || !self.frames[i - 1])
+                .filter(|&i| i > 0)
+                .map(|i| self.frame_start(i))
+        } else {
+            // Forward to the next speech
+            (index..self.frames.len())
+                .take_while(|&i| self.frame_start(i) < max)
+                .find(|&i| self.frames[i])
+                .map(|i| self.frame_start(i))
+        }
+    }
+
+    // End time of the speech containing or preceding `time`, searched within [min, max]
+    fn offset_near(&self, time: f64, min: f64, max: f64) -> Option<f64> {
+        let index = self.frame_at(time - FRAME_SECS / 2.0)?;
+        if self.frames[index] {
+            // Forward to where this stretch of speech ends
+            (index..self.frames.len())
+                .take_while(|&i| self.frame_start(i) < max)
+                .find(|&i| i + 1 == self.frames.len() || !self.frames[i + 1])
+                .filter(|&i| i + 1 < self.frames.len())
+                .map(|i| self.frame_start(i + 1))
+        } else {
+            // Back to the previous speech
+            (0..=index)
+                .rev()
+                .take_while(|&i| self.frame_start(i + 1) > min)
+                .find(|&i| self.frames[i])
+                .map(|i| self.frame_start(i + 1))
+        }
+    }
+}
+
+// Snap the words that lie inside the analysed audio to its speech boundaries. Words keep their
+// order: a word never starts before the previous one ends or ends after the next one starts.
+pub fn align_words(words: &mut [WordInfo], activity: &SpeechActivity, report: &mut AlignmentReport) {
+    let mut previous_end = f64::MIN;
+    for i in 0..words.len() {
+        let (start, end) = (words[i].start, words[i].end);
+        if start < activity.start || end > activity.end() {
+            previous_end = end;
+            continue;
+        }
+        let next_start = words.get(i + 1).map(|w| w.start).unwrap_or(f64::MAX);
+        report.aligned_words += 1;
+
+        let new_start = activity
+            .onset_near(start, (start - MAX_SHIFT_SECS).max(previous_end), (start + MAX_SHIFT_SECS).min(end))
+            .unwrap_or(start);
+        let new_end = activity
+            .offset_near(end, (end - MAX_SHIFT_SECS).max(new_start), (end + MAX_SHIFT_SECS).min(next_start))
+            .unwrap_or(end);
+        let (new_start, new_end) = if new_end - new_start >= MIN_WORD_SECS { (new_start, new_end) } else { (start, end) };
+
+        words[i].start = new_start;
+        words[i].end = new_end;
+        previous_end = new_end;
+        if (new_start - start).abs() >= REPORT_THRESHOLD_SECS || (new_end - end).abs() >= REPORT_THRESHOLD_SECS {
+            report.moved.push(WordShift {
+                index: i,
+                word: words[i].word.clone(),
+                original_start: start,
+                original_end: end,
+                start: new_start,
+                end: new_end,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::test_words::word;
+
+    // Silence with tones at the given (start, end) seconds, at ALIGNMENT_SAMPLE_RATE
+    fn audio(duration: f64, speech: &[(f64, f64)]) -> Vec<i16> {
+        let rate = ALIGNMENT_SAMPLE_RATE as f64;
+        (0..(duration * rate) as usize)
+            .map(|n| {
+                let t = n as f64 / rate;
+                let loud = speech.iter().any(|&(s, e)| t >= s && t < e);
+                let amplitude = if loud { 8000.0 } else { 30.0 };
+                (amplitude * (t * 220.0 * std::f64::consts::TAU).sin()) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_snaps_to_speech_boundaries() {
+        let samples = audio(3.0, &[(0.5, 1.0), (1.5, 2.2)]);
+        let activity = SpeechActivity::from_samples(&samples, ALIGNMENT_SAMPLE_RATE, 10.0).unwrap();
+        // Whisper-style drift: first word starts late and ends early, second starts early
+        let mut words = vec![word("hello", 10.62, 10.9), word("world", 11.35, 12.3)];
+        let mut report = AlignmentReport::default();
+        align_words(&mut words, &activity, &mut report);
+
+        assert!((words[0].start - 10.5).abs() < 0.015, "{}", words[0].start);
+        assert!((words[0].end - 11.0).abs() < 0.015, "{}", words[0].end);
+        assert!((words[1].start - 11.5).abs() < 0.015, "{}", words[1].start);
+        assert!((words[1].end - 12.2).abs() < 0.015, "{}", words[1].end);
+        assert_eq!(report.aligned_words, 2);
+        assert_eq!(report.moved.len(), 2);
+    }
+
+    #[test]
+    fn test_leaves_continuous_speech_and_flat_audio_alone() {
+        let samples = audio(3.0, &[(0.2, 2.8)]);
+        let activity = SpeechActivity::from_samples(&samples, ALIGNMENT_SAMPLE_RATE, 0.0).unwrap();
+        let mut words = vec![word("one", 1.0, 1.4), word("two", 1.4, 1.8)];
+        let mut report = AlignmentReport::default();
+        align_words(&mut words, &activity, &mut report);
+        assert!(report.moved.is_empty());
+
+        let flat = audio(3.0, &[]);
+        assert!(SpeechActivity::from_samples(&flat, ALIGNMENT_SAMPLE_RATE, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_silences() {
+        let samples = audio(4.0, &[(0.5, 1.5), (1.6, 2.5), (3.0, 3.5)]);
+        let activity = SpeechActivity::from_samples(&samples, ALIGNMENT_SAMPLE_RATE, 100.0).unwrap();
+        let silences = activity.silences(0.3);
+        // The 0.1s pause is too short; the edges and the 0.5s pause qualify
+        assert_eq!(silences.len(), 3, "{:?}", silences);
+        assert!((silences[1].0 - 102.5).abs() < 0.015 && (silences[1].1 - 103.0).abs() < 0.015, "{:?}", silences);
+    }
+}
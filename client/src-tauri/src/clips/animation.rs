@@ -0,0 +1,620 @@
+use super::captions::ClipWord;
+use super::script::needs_space_between;
+use super::text_metrics::{rounded_rect_drawing, WordBox};
+
+// Subtitle animation styles. Every style renders through plain ASS override tags, so the
+// same input always produces the same subtitle file (and therefore the same cached render).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationStyle {
+    // Words shown without any animation
+    Static,
+    // Active word scales up to 115% (the original export look)
+    Pop,
+    // Words fill with color as they are spoken (\kf)
+    Karaoke,
+    // Active word squashes and stretches back into place
+    Bounce,
+    // Each word fades in when it is spoken
+    Fade,
+    // Each word is typed out character by character when it is spoken
+    Typewriter,
+    // Each page slides up into place as it appears
+    SlideUp,
+    // An opaque box is drawn behind the active word
+    HighlightBox,
+}
+
+impl AnimationStyle {
+    // Parse SubtitleSettings.animation_style. "word-by-word" and "fade" are legacy values whose
+    // exports always used the pop, so they keep it (migration 034 moved stored presets with
+    // them, and with the old "none", to "pop").
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "none" | "static" => AnimationStyle::Static,
+            "pop" | "word-by-word" | "fade" => AnimationStyle::Pop,
+            "karaoke" => AnimationStyle::Karaoke,
+            "bounce" => AnimationStyle::Bounce,
+            "fade-in" => AnimationStyle::Fade,
+            "typewriter" => AnimationStyle::Typewriter,
+            "slide-up" | "slideup" => AnimationStyle::SlideUp,
+            "highlight-box" | "highlight" => AnimationStyle::HighlightBox,
+            _ => AnimationStyle::Pop,
+        }
+    }
+
+    // Whether the style needs the "{prefix}HighlightBox" ASS style
+    pub fn needs_highlight_box_style(&self) -> bool {
+        matches!(self, AnimationStyle::HighlightBox)
+    }
+}
+
+// One Dialogue line
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssEvent {
+    pub layer: i32,
+    pub start: f64,
+    pub end: f64,
+    pub style: String,
+    pub text: String,
+}
+
+// Everything an animation needs to render one chunk (page) of words
+pub struct ChunkContext<'a> {
+    pub words: &'a [ClipWord],
+    pub visible_start: f64,
+    pub visible_end: f64,
+    // Style name prefix ("" for the default caption styles)
+    pub style_prefix: &'a str,
+    pub pos_x: f64,
+    pub pos_y: f64,
+    pub font_size: f32,
+    pub weight_tag: String,
+    pub word_separator: String,
+    // Words that start a new line (the page is broken with \\N before them)
+    pub line_starts: Vec<usize>,
+    pub emphasis: WordEmphasis,
+}
+
+// Colors/opacity for the active and inactive words (no active color or dimming = no emphasis)
+#[derive(Debug, Clone, Default)]
+pub struct WordEmphasis {
+    // ASS override colors (e.g. "&H00FFFF&")
+    pub base_color: String,
+    pub active_color: Option<String>,
+    // ASS alpha for words that are not being spoken (e.g. "&H80&")
+    pub inactive_alpha: Option<String>,
+}
+
+impl WordEmphasis {
+    pub fn is_enabled(&self) -> bool {
+        self.active_color.is_some() || self.inactive_alpha.is_some()
+    }
+
+    // Override tags for a word, depending on whether it is the active word
+    fn word_tags(&self, is_active: bool) -> String {
+        if !self.is_enabled() {
+            return String::new();
+        }
+        if is_active {
+            format!("{{\\1c{}\\alpha&H00&}}", self.active_color.as_deref().unwrap_or(&self.base_color))
+        } else {
+            format!("{{\\1c{}\\alpha{}}}", self.base_color, self.visible_alpha())
+        }
+    }
+
+    // Alpha of a visible word that is not active
+    fn visible_alpha(&self) -> &str {
+        self.inactive_alpha.as_deref().unwrap_or("&H00&")
+    }
+
+    fn active_color_tag(&self) -> String {
+        self.active_color.as_ref().map(|c| format!("{{\\1c{}}}", c)).unwrap_or_default()
+    }
+}
+
+// Rounded box drawn behind text (the active word or the whole page)
+pub struct BoxStyle {
+    // ASS override color and alpha (e.g. "&H00FFFF&", "&H00&")
+    pub color: String,
+    pub alpha: String,
+    pub padding: f64,
+    pub radius: f64,
+    // Height of the box around one line of text (without padding)
+    pub height: f64,
+}
+
+impl BoxStyle {
+    // Dialogue text drawing the box with its top-left corner at (left, top)
+    fn drawing_text(&self, left: f64, top: f64, width: f64, height: f64) -> String {
+        format!(
+            "{{\\an7\\pos({:.0},{:.0})\\bord0\\shad0\\1c{}\\1a{}\\p1}}{}{{\\p0}}",
+            left, top, self.color, self.alpha, rounded_rect_drawing(width, height, self.radius)
+        )
+    }
+}
+
+impl ChunkContext<'_> {
+    fn pos_tag(&self) -> String {
+        format!("{{\\pos({:.0},{:.0})}}", self.pos_x, self.pos_y)
+    }
+
+    fn style(&self, name: &str) -> String {
+        format!("{}{}", self.style_prefix, name)
+    }
+
+    // Emit the same text on both border layers (outer border below, inner border above)
+    fn border_pair(&self, layers: (i32, i32), start: f64, end: f64, text: String) -> Vec<AssEvent> {
+        vec![
+            AssEvent { layer: layers.0, start, end, style: self.style("Border2Layer"), text: text.clone() },
+            AssEvent { layer: layers.1, start, end, style: self.style("Border1Layer"), text },
+        ]
+    }
+
+    // Join rendered words with the word separator, breaking lines before the line starts
+    // (Chinese/Japanese words are joined without a separator)
+    fn join_words(&self, parts: Vec<String>) -> String {
+        let mut text = String::new();
+        for (k, part) in parts.into_iter().enumerate() {
+            if self.line_starts.contains(&k) {
+                text.push_str("\\N");
+            } else if k > 0 && needs_space_between(&self.words[k - 1].word, &self.words[k].word) {
+                text.push_str(&self.word_separator);
+            }
+            text.push_str(&part);
+        }
+        text
+    }
+
+    fn plain_text(&self) -> String {
+        let parts = self.words
+            .iter()
+            .map(|w| format!("{}{}", self.weight_tag, w.word))
+            .collect();
+        self.join_words(parts)
+    }
+
+    // Page text with the active-word emphasis applied (same as plain_text without emphasis)
+    fn styled_text(&self, active: Option<usize>) -> String {
+        let parts = self.words
+            .iter()
+            .enumerate()
+            .map(|(k, w)| format!("{}{}{}", self.weight_tag, self.emphasis.word_tags(Some(k) == active), w.word))
+            .collect();
+        self.join_words(parts)
+    }
+}
+
+// Calculate animation duration for a word (matches VideoPlayer.vue logic)
+pub fn calculate_animation_duration(word_duration: f64) -> u32 {
+    // Returns duration in milliseconds
+
+    // For very short words (under 50ms), use instant transition
+    if word_duration < 0.05 {
+        return 0;
+    }
+
+    // For short words (50-100ms), use 30% of duration for responsive animation
+    if word_duration < 0.1 {
+        return ((word_duration * 0.3) * 1000.0) as u32;
+    }
+
+    // For medium words (100-200ms), use 35% of duration
+    if word_duration < 0.2 {
+        return ((word_duration * 0.35) * 1000.0) as u32;
+    }
+
+    // For normal words (200-400ms), use 40% of duration
+    if word_duration < 0.4 {
+        return ((word_duration * 0.4) * 1000.0) as u32;
+    }
+
+    // For longer words (400ms+), use 45% but cap at 200ms to prevent overly slow animations
+    let calculated_duration = word_duration * 0.45;
+    let capped_duration = calculated_duration.min(0.2);
+    (capped_duration * 1000.0) as u32
+}
+
+// Split a chunk's visibility into intervals at every word boundary, with the word active in each.
+// Each interval is one "state" of the page, so per-word effects never overlap.
+fn chunk_intervals(ctx: &ChunkContext) -> Vec<(f64, f64, Option<usize>)> {
+    // We have a timeline of points: visible_start, w0.start, w0.end, w1.start, w1.end... visible_end.
+    // Sort and deduplicate these points.
+    let mut points = vec![ctx.visible_start];
+    for word in ctx.words {
+        points.push(word.start);
+        points.push(word.end);
+    }
+    points.push(ctx.visible_end);
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    points.dedup();
+
+    points
+        .windows(2)
+        .filter(|pair| pair[1] - pair[0] >= 0.01) // Skip tiny intervals
+        .map(|pair| {
+            // A word is active if t_mid is inside [word.start, word.end]
+            let t_mid = (pair[0] + pair[1]) / 2.0;
+            let active = ctx.words.iter().position(|w| t_mid >= w.start && t_mid <= w.end);
+            (pair[0], pair[1], active)
+        })
+        .collect()
+}
+
+// Milliseconds from the start of an event to a word's start
+fn offset_ms(event_start: f64, word_start: f64) -> u32 {
+    if word_start > event_start {
+        ((word_start - event_start) * 1000.0) as u32
+    } else {
+        0
+    }
+}
+
+// Text with only one word visible (others keep their spacing but are fully transparent)
+fn isolate_word(ctx: &ChunkContext, active_idx: usize, active_tags: &str) -> String {
+    let parts = ctx.words
+        .iter()
+        .enumerate()
+        .map(|(k, word)| {
+            if k == active_idx {
+                format!("{}{}{}", ctx.weight_tag, active_tags, word.word)
+            } else {
+                // Use {\alpha&HFF&} to make word invisible (maintains spacing)
+                // Still include weight tag to maintain spacing metrics
+                format!("{}{{\\alpha&HFF&}}{}", ctx.weight_tag, word.word)
+            }
+        })
+        .collect();
+    ctx.join_words(parts)
+}
+
+// Render one chunk of words with the given animation style
+pub fn render_chunk(style: AnimationStyle, ctx: &ChunkContext) -> Vec<AssEvent> {
+    if ctx.words.is_empty() || ctx.visible_end - ctx.visible_start < 0.01 {
+        return Vec::new();
+    }
+
+    match style {
+        AnimationStyle::Static if !ctx.emphasis.is_enabled() => {
+            ctx.border_pair((0, 2), ctx.visible_start, ctx.visible_end, format!("{}{}", ctx.pos_tag(), ctx.plain_text()))
+        },
+        AnimationStyle::Static => render_emphasis_only(ctx),
+        AnimationStyle::Pop | AnimationStyle::Bounce => render_active_word_overlay(style, ctx),
+        AnimationStyle::Karaoke => render_karaoke(ctx),
+        AnimationStyle::Fade | AnimationStyle::Typewriter => render_reveal(style, ctx),
+        AnimationStyle::SlideUp => render_slide_up(ctx),
+        AnimationStyle::HighlightBox => render_highlight_box(ctx),
+    }
+}
+
+// Strategy: Render text in four layers for dual borders + animation
+// Layer 0: Border2Layer base text (shadow + outer border)
+// Layer 1: Border2Layer active word animation (shadow + outer border, scaled)
+// Layer 2: Border1Layer base text (inner border)
+// Layer 3: Border1Layer active word animation (inner border, scaled)
+fn render_active_word_overlay(style: AnimationStyle, ctx: &ChunkContext) -> Vec<AssEvent> {
+    let mut events = Vec::new();
+
+    for (t_start, t_end, active) in chunk_intervals(ctx) {
+        let base_text = format!("{}{}", ctx.pos_tag(), ctx.styled_text(active));
+        events.extend(ctx.border_pair((0, 2), t_start, t_end, base_text));
+
+        let Some(active_idx) = active else { continue };
+        let active_word = &ctx.words[active_idx];
+        let anim_duration_ms = calculate_animation_duration(active_word.end - active_word.start);
+        // Calculate when this word starts within the current interval
+        let word_start = offset_ms(t_start, active_word.start);
+
+        let tags = match style {
+            AnimationStyle::Bounce => {
+                // Stretch up past the target, then settle back to normal size
+                let half = anim_duration_ms / 2;
+                format!(
+                    "{{\\r\\t({},{},\\fscx108\\fscy125)\\t({},{},\\fscx100\\fscy100)}}",
+                    word_start, word_start + half, word_start + half, word_start + anim_duration_ms
+                )
+            },
+            _ => format!("{{\\r\\t({},{},\\fscx115\\fscy115)}}", word_start, word_start + anim_duration_ms),
+        };
+
+        // Active word with animation, other words invisible to keep the layout
+        let overlay_text = format!("{}{}", ctx.pos_tag(), pop_overlay_text(ctx, active_idx, &tags));
+        events.extend(ctx.border_pair((1, 3), t_start, t_end, overlay_text));
+    }
+    events
+}
+
+// Active word with the scale tags and a reset after it so following (invisible) words keep their metrics
+fn pop_overlay_text(ctx: &ChunkContext, active_idx: usize, tags: &str) -> String {
+    let parts = ctx.words
+        .iter()
+        .enumerate()
+        .map(|(k, word)| {
+            if k == active_idx {
+                format!("{}{}{}{}{{\\fscx100\\fscy100}}", ctx.weight_tag, tags, ctx.emphasis.active_color_tag(), word.word)
+            } else {
+                format!("{}{{\\alpha&HFF&}}{}", ctx.weight_tag, word.word)
+            }
+        })
+        .collect();
+    ctx.join_words(parts)
+}
+
+// Karaoke fill: one event per page, with \kf timings in centiseconds from the page start.
+// Unsung words use the dimmed secondary colour and fill to the primary colour as they are spoken.
+fn render_karaoke(ctx: &ChunkContext) -> Vec<AssEvent> {
+    // Rounded from absolute times so per-word rounding never drifts across the page
+    let to_cs = |t: f64| ((t - ctx.visible_start).max(0.0) * 100.0).round() as i64;
+
+    let mut cursor_cs = 0;
+    let mut parts = Vec::new();
+    for word in ctx.words {
+        let start_cs = to_cs(word.start).max(cursor_cs);
+        let end_cs = to_cs(word.end).max(start_cs);
+        // Silence before the word is an empty syllable
+        let gap = if start_cs > cursor_cs { format!("{{\\k{}}}", start_cs - cursor_cs) } else { String::new() };
+        parts.push(format!("{}{}{{\\kf{}}}{}", gap, ctx.weight_tag, end_cs - start_cs, word.word));
+        cursor_cs = end_cs;
+    }
+
+    // Unsung words are dimmed (the inactive opacity if set); the active color becomes the fill color
+    let unsung_alpha = ctx.emphasis.inactive_alpha.as_deref().unwrap_or("&H99&");
+    let fill_tags = match &ctx.emphasis.active_color {
+        Some(active) => format!("\\1c{}\\2c{}", active, ctx.emphasis.base_color),
+        None => String::new(),
+    };
+    let text = format!("{}{{{}\\2a{}}}{}", ctx.pos_tag(), fill_tags, unsung_alpha, ctx.join_words(parts));
+    ctx.border_pair((0, 2), ctx.visible_start, ctx.visible_end, text)
+}
+
+// Fade/typewriter: words already spoken are visible, the active word is revealed, later words are hidden
+fn render_reveal(style: AnimationStyle, ctx: &ChunkContext) -> Vec<AssEvent> {
+    let mut events = Vec::new();
+
+    for (t_start, t_end, active) in chunk_intervals(ctx) {
+        let parts = ctx.words
+            .iter()
+            .enumerate()
+            .map(|(k, word)| {
+                let reveal = if Some(k) == active {
+                    let word_start = offset_ms(t_start, word.start);
+                    let color = ctx.emphasis.active_color_tag();
+                    if word.start < t_start - 0.001 {
+                        // Revealed in an earlier interval
+                        format!("{}{{\\alpha&H00&}}", color)
+                    } else if style == AnimationStyle::Typewriter {
+                        return format!("{}{}{}", ctx.weight_tag, color, typewriter_word(&word.word, word_start, word.end - word.start));
+                    } else {
+                        let fade_ms = calculate_animation_duration(word.end - word.start).max(1);
+                        format!("{}{{\\alpha&HFF&\\t({},{},\\alpha&H00&)}}", color, word_start, word_start + fade_ms)
+                    }
+                } else if word.start < t_start + 0.001 {
+                    let color = if ctx.emphasis.is_enabled() { format!("\\1c{}", ctx.emphasis.base_color) } else { String::new() };
+                    format!("{{{}\\alpha{}}}", color, ctx.emphasis.visible_alpha())
+                } else {
+                    "{\\alpha&HFF&}".to_string()
+                };
+                format!("{}{}{}", ctx.weight_tag, reveal, word.word)
+            })
+            .collect();
+        let text = ctx.join_words(parts);
+
+        events.extend(ctx.border_pair((0, 2), t_start, t_end, format!("{}{}", ctx.pos_tag(), text)));
+    }
+    events
+}
+
+// Reveal the characters of a word evenly over its duration (override blocks inside the word,
+// e.g. decoration or emoji font tags, are kept as they are)
+fn typewriter_word(word: &str, start_ms: u32, duration: f64) -> String {
+    let mut visible: Vec<char> = Vec::new();
+    let mut in_tags = false;
+    for c in word.chars() {
+        match c {
+            '{' => in_tags = true,
+            '}' => in_tags = false,
+            _ if !in_tags => visible.push(c),
+            _ => {}
+        }
+    }
+    let per_char_ms = (duration * 1000.0) / visible.len().max(1) as f64;
+
+    let mut out = String::new();
+    let mut index = 0;
+    let mut in_tags = false;
+    for c in word.chars() {
+        if c == '{' || in_tags {
+            in_tags = c != '}';
+            out.push(c);
+            continue;
+        }
+        let at = start_ms + (index as f64 * per_char_ms) as u32;
+        out.push_str(&format!("{{\\alpha&HFF&\\t({},{},\\alpha&H00&)}}{}", at, at + 1, c));
+        index += 1;
+    }
+    out
+}
+
+// Slide the whole page up into its position (with a short fade) when it appears
+fn render_slide_up(ctx: &ChunkContext) -> Vec<AssEvent> {
+    const SLIDE_MS: u32 = 180;
+    let rise = (ctx.font_size as f64 * 0.6).round();
+
+    chunk_intervals(ctx)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, (t_start, t_end, active))| {
+            // Only the first state of the page moves; later states sit at the final position
+            let position = if i == 0 {
+                format!(
+                    "{{\\move({:.0},{:.0},{:.0},{:.0},0,{})\\fad({},0)}}",
+                    ctx.pos_x, ctx.pos_y + rise, ctx.pos_x, ctx.pos_y, SLIDE_MS, SLIDE_MS
+                )
+            } else {
+                ctx.pos_tag()
+            };
+            ctx.border_pair((0, 2), t_start, t_end, format!("{}{}", position, ctx.styled_text(active)))
+        })
+        .collect()
+}
+
+// Static words with only the active-word color/dimming changing
+fn render_emphasis_only(ctx: &ChunkContext) -> Vec<AssEvent> {
+    chunk_intervals(ctx)
+        .into_iter()
+        .flat_map(|(t_start, t_end, active)| {
+            ctx.border_pair((0, 2), t_start, t_end, format!("{}{}", ctx.pos_tag(), ctx.styled_text(active)))
+        })
+        .collect()
+}
+
+// Opaque box behind the active word (layer 0), text on layers 1 and 2 above it
+fn render_highlight_box(ctx: &ChunkContext) -> Vec<AssEvent> {
+    let mut events = Vec::new();
+
+    for (t_start, t_end, active) in chunk_intervals(ctx) {
+        let base_text = format!("{}{}", ctx.pos_tag(), ctx.styled_text(active));
+        if let Some(active_idx) = active {
+            events.push(AssEvent {
+                layer: 0,
+                start: t_start,
+                end: t_end,
+                style: ctx.style("HighlightBox"),
+                text: format!("{}{}", ctx.pos_tag(), isolate_word(ctx, active_idx, "")),
+            });
+        }
+        events.extend(ctx.border_pair((1, 2), t_start, t_end, base_text));
+    }
+    events
+}
+
+// Rounded boxes behind the active word, drawn with \p1 at the word's laid-out position.
+// They go on layer 0; the caller moves the text layers above them.
+pub fn render_active_word_boxes(ctx: &ChunkContext, word_boxes: &[WordBox], style: &BoxStyle) -> Vec<AssEvent> {
+    chunk_intervals(ctx)
+        .into_iter()
+        .filter_map(|(t_start, t_end, active)| {
+            let word_box = word_boxes.get(active?)?;
+            let width = (word_box.right - word_box.left) + style.padding * 2.0;
+            let height = style.height + style.padding * 2.0;
+            let left = word_box.left - style.padding;
+            let top = word_box.center_y - height / 2.0;
+            Some(AssEvent {
+                layer: 0,
+                start: t_start,
+                end: t_end,
+                style: ctx.style("Border1Layer"),
+                text: style.drawing_text(left, top, width, height),
+            })
+        })
+        .collect()
+}
+
+// Background box around the whole page (every line of the laid-out words), shown while the page is visible
+pub fn render_page_background(ctx: &ChunkContext, word_boxes: &[WordBox], style: &BoxStyle) -> Option<AssEvent> {
+    let left = word_boxes.iter().map(|b| b.left).fold(f64::INFINITY, f64::min);
+    let right = word_boxes.iter().map(|b| b.right).fold(f64::NEG_INFINITY, f64::max);
+    let first_line = word_boxes.iter().map(|b| b.center_y).fold(f64::INFINITY, f64::min);
+    let last_line = word_boxes.iter().map(|b| b.center_y).fold(f64::NEG_INFINITY, f64::max);
+    if !left.is_finite() || !right.is_finite() || ctx.visible_end - ctx.visible_start < 0.01 {
+        return None;
+    }
+
+    let top = first_line - style.height / 2.0 - style.padding;
+    let bottom = last_line + style.height / 2.0 + style.padding;
+    Some(AssEvent {
+        layer: 0,
+        start: ctx.visible_start,
+        end: ctx.visible_end,
+        style: ctx.style("Border1Layer"),
+        text: style.drawing_text(left - style.padding, top, right - left + style.padding * 2.0, bottom - top),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> Vec<ClipWord> {
+        vec![
+            ClipWord { word: "one".to_string(), start: 0.0, end: 0.5, segment_index: 0, speaker: None },
+            ClipWord { word: "two".to_string(), start: 0.7, end: 1.0, segment_index: 0, speaker: None },
+        ]
+    }
+
+    fn ctx(words: &[ClipWord]) -> ChunkContext<'_> {
+        ChunkContext {
+            words,
+            visible_start: 0.0,
+            visible_end: 1.0,
+            style_prefix: "",
+            pos_x: 540.0,
+            pos_y: 900.0,
+            font_size: 60.0,
+            weight_tag: String::new(),
+            word_separator: " ".to_string(),
+            line_starts: Vec::new(),
+            emphasis: WordEmphasis::default(),
+        }
+    }
+
+    #[test]
+    fn test_legacy_values_keep_pop() {
+        assert_eq!(AnimationStyle::from_setting("word-by-word"), AnimationStyle::Pop);
+        assert_eq!(AnimationStyle::from_setting("fade"), AnimationStyle::Pop);
+        assert_eq!(AnimationStyle::from_setting("none"), AnimationStyle::Static);
+        assert_eq!(AnimationStyle::from_setting("fade-in"), AnimationStyle::Fade);
+        assert_eq!(AnimationStyle::from_setting("Karaoke"), AnimationStyle::Karaoke);
+    }
+
+    #[test]
+    fn test_karaoke_timings_include_gaps() {
+        let words = words();
+        let events = render_chunk(AnimationStyle::Karaoke, &ctx(&words));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].text, "{\\pos(540,900)}{\\2a&H99&}{\\kf50}one {\\k20}{\\kf30}two");
+    }
+
+    #[test]
+    fn test_pop_matches_original_layers() {
+        let words = words();
+        let events = render_chunk(AnimationStyle::Pop, &ctx(&words));
+        // Intervals: [0,0.5] one active, [0.5,0.7] none, [0.7,1.0] two active
+        let layers: Vec<i32> = events.iter().map(|e| e.layer).collect();
+        assert_eq!(layers, vec![0, 2, 1, 3, 0, 2, 0, 2, 1, 3]);
+        assert_eq!(events[2].text, "{\\pos(540,900)}{\\r\\t(0,200,\\fscx115\\fscy115)}one{\\fscx100\\fscy100} {\\alpha&HFF&}two");
+    }
+
+    #[test]
+    fn test_emphasis_colors_active_and_dims_others() {
+        let words = words();
+        let mut context = ctx(&words);
+        context.emphasis = WordEmphasis {
+            base_color: "&HFFFFFF&".to_string(),
+            active_color: Some("&H00FFFF&".to_string()),
+            inactive_alpha: Some("&H80&".to_string()),
+        };
+        let events = render_chunk(AnimationStyle::Static, &context);
+        assert_eq!(events[0].text, "{\\pos(540,900)}{\\1c&H00FFFF&\\alpha&H00&}one {\\1c&HFFFFFF&\\alpha&H80&}two");
+    }
+
+    #[test]
+    fn test_page_background_covers_all_lines() {
+        let words = words();
+        let boxes = [
+            WordBox { left: 400.0, right: 500.0, center_y: 840.0 },
+            WordBox { left: 450.0, right: 620.0, center_y: 960.0 },
+        ];
+        let style = BoxStyle {
+            color: "&H000000&".to_string(),
+            alpha: "&H00&".to_string(),
+            padding: 10.0,
+            radius: 0.0,
+            height: 72.0,
+        };
+        let event = render_page_background(&ctx(&words), &boxes, &style).unwrap();
+        assert_eq!((event.layer, event.start, event.end), (0, 0.0, 1.0));
+        assert!(event.text.starts_with("{\\an7\\pos(390,794)"));
+        assert!(event.text.contains("m 0 0 l 240 0 l 240 212 l 0 212"));
+    }
+}
@@ -0,0 +1,290 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Content-addressed cache for intermediate clip build artifacts (cropped segment encodes,
+// prepared intros/outros, concatenated base videos, final renders). Entries are keyed by a
+// hash of every input that affects the file, so a rebuild only redoes the stages whose
+// inputs changed. Least-recently-used entries are evicted once the limits are exceeded.
+
+const DEFAULT_MAX_BYTES: u64 = 20 * 1024 * 1024 * 1024; // 20 GB
+const DEFAULT_MAX_ENTRIES: usize = 2000;
+// Entries used this recently are never evicted (they may belong to a build in progress)
+const EVICTION_GRACE_SECS: u64 = 15 * 60;
+// Partial files older than this are leftovers from crashed builds
+const STALE_PARTIAL_SECS: u64 = 24 * 60 * 60;
+
+// Current cache limits (configurable from the frontend)
+static CACHE_LIMITS: Lazy<Mutex<(u64, usize)>> = Lazy::new(|| Mutex::new((DEFAULT_MAX_BYTES, DEFAULT_MAX_ENTRIES)));
+
+// Per-key locks so concurrent builds produce each artifact only once
+static KEY_LOCKS: Lazy<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Source fingerprints, keyed by (path, size, modified time)
+type FingerprintKey = (String, u64, u64);
+static SOURCE_FINGERPRINTS: Lazy<Mutex<HashMap<FingerprintKey, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactCacheStats {
+    pub path: String,
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+    pub max_entries: usize,
+}
+
+pub struct ArtifactCache {
+    dir: std::path::PathBuf,
+}
+
+// Get the artifact cache in the storage temp directory
+pub fn artifact_cache() -> Result<ArtifactCache, String> {
+    let paths = crate::storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let dir = paths.temp.join("artifact_cache");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create artifact cache directory: {}", e))?;
+    Ok(ArtifactCache { dir })
+}
+
+// Set the cache limits and evict immediately if the cache is now over them
+pub fn set_cache_limits(max_bytes: u64, max_entries: usize) -> Result<(), String> {
+    {
+        let mut limits = CACHE_LIMITS.lock().unwrap();
+        *limits = (max_bytes, max_entries);
+    }
+    artifact_cache()?.evict();
+    Ok(())
+}
+
+// Build a cache key from a stage name and every input that affects the stage output
+pub fn artifact_key(stage: &str, inputs: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(stage.as_bytes());
+    for input in inputs {
+        hasher.update([0x1f]);
+        hasher.update(input.as_bytes());
+    }
+    let digest = format!("{:x}", hasher.finalize());
+    format!("{}-{}", stage, &digest[..32])
+}
+
+// Fingerprint a source file: path, size, mtime and a hash of its first and last MB.
+// Hashing the whole file would take longer than most builds for multi-GB streams.
+pub async fn source_fingerprint(path: &str) -> Result<String, String> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || source_fingerprint_blocking(&path))
+        .await
+        .map_err(|e| format!("Failed to fingerprint source: {}", e))?
+}
+
+fn source_fingerprint_blocking(path: &str) -> Result<String, String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read source metadata for {}: {}", path, e))?;
+    let size = metadata.len();
+    let modified = metadata.modified().ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let cache_key = (path.to_string(), size, modified);
+    {
+        let fingerprints = SOURCE_FINGERPRINTS.lock().unwrap();
+        if let Some(fingerprint) = fingerprints.get(&cache_key) {
+            return Ok(fingerprint.clone());
+        }
+    }
+
+    const SAMPLE: u64 = 1024 * 1024;
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open source {}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; SAMPLE as usize];
+
+    let read = file.read(&mut buffer).map_err(|e| format!("Failed to read source: {}", e))?;
+    hasher.update(&buffer[..read]);
+    if size > SAMPLE * 2 {
+        file.seek(SeekFrom::Start(size - SAMPLE)).map_err(|e| format!("Failed to seek source: {}", e))?;
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read source: {}", e))?;
+        hasher.update(&buffer[..read]);
+    }
+
+    let fingerprint = format!("{}|{}|{}|{:x}", path, size, modified, hasher.finalize());
+    {
+        let mut fingerprints = SOURCE_FINGERPRINTS.lock().unwrap();
+        fingerprints.insert(cache_key, fingerprint.clone());
+    }
+    Ok(fingerprint)
+}
+
+// Hard link a cached artifact to its destination (falls back to a copy across filesystems)
+pub fn link_or_copy(src: &std::path::Path, dst: &std::path::Path) -> Result<(), String> {
+    if dst.exists() {
+        std::fs::remove_file(dst)
+            .map_err(|e| format!("Failed to replace {}: {}", dst.display(), e))?;
+    }
+    if std::fs::hard_link(src, dst).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(src, dst)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {} to {}: {}", src.display(), dst.display(), e))
+}
+
+fn touch(path: &std::path::Path) {
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(std::time::SystemTime::now());
+    }
+}
+
+fn lock_for_key(key: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let mut locks = KEY_LOCKS.lock().unwrap();
+    locks.entry(key.to_string()).or_default().clone()
+}
+
+fn release_key_lock(key: &str, lock: Arc<tokio::sync::Mutex<()>>) {
+    let mut locks = KEY_LOCKS.lock().unwrap();
+    // Only the map and this caller hold the lock - nobody else is waiting on it
+    if Arc::strong_count(&lock) <= 2 {
+        locks.remove(key);
+    }
+}
+
+impl ArtifactCache {
+    fn entry_path(&self, key: &str, extension: &str) -> std::path::PathBuf {
+        self.dir.join(format!("{}.{}", key, extension))
+    }
+
+    // Return the cached artifact for `key`, producing it with `produce` on a miss.
+    // `produce` receives a temporary path to write to; it is moved into place on success.
+    pub async fn get_or_create<F, Fut>(
+        &self,
+        key: &str,
+        extension: &str,
+        produce: F
+    ) -> Result<std::path::PathBuf, String>
+    where
+        F: FnOnce(std::path::PathBuf) -> Fut,
+        Fut: std::future::Future<Output = Result<(), String>>,
+    {
+        let final_path = self.entry_path(key, extension);
+        let key_lock = lock_for_key(key);
+        let guard = key_lock.lock().await;
+
+        if final_path.exists() {
+            println!("[Rust] Artifact cache hit: {}", key);
+            touch(&final_path);
+            drop(guard);
+            release_key_lock(key, key_lock);
+            return Ok(final_path);
+        }
+
+        println!("[Rust] Artifact cache miss: {}", key);
+        // Keep the real extension last so FFmpeg picks the right muxer
+        let partial_path = self.dir.join(format!("{}.partial-{}.{}", key, uuid::Uuid::new_v4(), extension));
+        let result = match produce(partial_path.clone()).await {
+            Ok(()) => std::fs::rename(&partial_path, &final_path)
+                .map_err(|e| format!("Failed to store artifact {}: {}", key, e)),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            let _ = std::fs::remove_file(&partial_path);
+        }
+
+        drop(guard);
+        release_key_lock(key, key_lock);
+        result?;
+
+        self.evict();
+        Ok(final_path)
+    }
+
+    // Current cache size and limits
+    pub fn stats(&self) -> ArtifactCacheStats {
+        let (max_bytes, max_entries) = *CACHE_LIMITS.lock().unwrap();
+        let entries = self.list_entries();
+        ArtifactCacheStats {
+            path: self.dir.to_string_lossy().to_string(),
+            entries: entries.len(),
+            total_bytes: entries.iter().map(|(_, size, _)| size).sum(),
+            max_bytes,
+            max_entries,
+        }
+    }
+
+    // Remove every entry (in-flight partial files are left alone)
+    pub fn clear(&self) -> Result<usize, String> {
+        let entries = self.list_entries();
+        for (path, _, _) in &entries {
+            std::fs::remove_file(path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+        Ok(entries.len())
+    }
+
+    // Evict least-recently-used entries until the cache is within its limits
+    pub fn evict(&self) {
+        let (max_bytes, max_entries) = *CACHE_LIMITS.lock().unwrap();
+        let now = std::time::SystemTime::now();
+
+        self.remove_stale_partials(now);
+
+        let mut entries = self.list_entries();
+        let mut total_bytes: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        let mut count = entries.len();
+        if total_bytes <= max_bytes && count <= max_entries {
+            return;
+        }
+
+        // Oldest access first
+        entries.sort_by_key(|(_, _, accessed)| *accessed);
+        for (path, size, accessed) in entries {
+            if total_bytes <= max_bytes && count <= max_entries {
+                break;
+            }
+            let age = now.duration_since(accessed).map(|d| d.as_secs()).unwrap_or(0);
+            if age < EVICTION_GRACE_SECS {
+                continue;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                println!("[Rust] Evicted artifact: {}", path.display());
+                total_bytes = total_bytes.saturating_sub(size);
+                count -= 1;
+            }
+        }
+    }
+
+    fn remove_stale_partials(&self, now: std::time::SystemTime) {
+        let Ok(read_dir) = std::fs::read_dir(&self.dir) else { return };
+        for entry in read_dir.flatten() {
+            let is_partial = entry.file_name().to_string_lossy().contains(".partial-");
+            let modified = entry.metadata().ok().and_then(|m| m.modified().ok());
+            if let (true, Some(modified)) = (is_partial, modified) {
+                let age = now.duration_since(modified).map(|d| d.as_secs()).unwrap_or(0);
+                if age > STALE_PARTIAL_SECS {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+    }
+
+    // Completed entries as (path, size, last access)
+    fn list_entries(&self) -> Vec<(std::path::PathBuf, u64, std::time::SystemTime)> {
+        let Ok(read_dir) = std::fs::read_dir(&self.dir) else { return Vec::new() };
+        read_dir
+            .flatten()
+            .filter(|entry| !entry.file_name().to_string_lossy().contains(".partial-"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+            })
+            .collect()
+    }
+}
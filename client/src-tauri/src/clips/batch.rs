@@ -0,0 +1,247 @@
+use std::sync::{Arc, Mutex};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use tauri::Emitter;
+
+use super::types::{BatchClipItem, ClipBatchProgress, ClipBatchSummary, ClipBuildResult, ClipExportOptions, ClipRenderOptions, SubtitleSettings, WordInfo, WhisperSegment};
+use super::video_info::{get_video_info, IntroOutroCache};
+use super::encoder::detect_hardware_encoder;
+use super::orchestrator::{build_clip_internal_simple, get_or_create_run_folder};
+use super::scheduler::cpu_budget;
+use super::ACTIVE_CLIP_BUILDS;
+
+// Settings shared by every clip in a batch build
+#[derive(Debug, Clone)]
+pub struct BatchSharedSettings {
+    pub project_id: String,
+    pub video_path: String,
+    pub subtitle_settings: Option<SubtitleSettings>,
+    pub transcript_words: Option<Vec<WordInfo>>,
+    pub transcript_segments: Option<Vec<WhisperSegment>>,
+    pub max_words: Option<usize>,
+    pub aspect_ratios: Vec<String>,
+    pub quality: String,
+    pub frame_rate: u32,
+    pub output_format: String,
+    pub run_number: Option<u32>,
+    pub intro_path: Option<String>,
+    pub intro_duration: Option<f64>,
+    pub outro_path: Option<String>,
+    pub outro_duration: Option<f64>,
+    pub export_options: Option<ClipExportOptions>,
+    pub render_options: Option<ClipRenderOptions>,
+}
+
+// Summary manifest written next to the run folders after a batch completes
+#[derive(Debug, Serialize)]
+struct ClipBatchManifest<'a> {
+    batch_id: &'a str,
+    project_id: &'a str,
+    video_path: &'a str,
+    created_at: u64,
+    aspect_ratios: &'a [String],
+    quality: &'a str,
+    frame_rate: u32,
+    output_format: &'a str,
+    encoder: String,
+    run_number: Option<u32>,
+    summary: &'a ClipBatchSummary,
+}
+
+// Number of clips processed at the same time. FFmpeg work is throttled separately by the
+// global CPU budget, so this only bounds temp disk usage and the number of idle builds.
+fn clip_concurrency() -> usize {
+    (cpu_budget() as usize / 4).clamp(1, 4)
+}
+
+// Run a batch build. All clip IDs must already be registered in ACTIVE_CLIP_BUILDS;
+// each one is removed as it finishes (or skipped if it was cancelled before starting).
+pub async fn run_clip_batch(
+    app: tauri::AppHandle,
+    batch_id: String,
+    shared: BatchSharedSettings,
+    clips: Vec<BatchClipItem>
+) -> ClipBatchSummary {
+    let started_at = std::time::Instant::now();
+    let total = clips.len();
+    println!("[Rust] Starting batch {} with {} clips", batch_id, total);
+
+    // Do the shared work once up front: probe the source and the available encoders.
+    // Both results are cached, so every clip build below reuses them.
+    if let Err(e) = get_video_info(&app, &shared.video_path).await {
+        println!("[Rust] Batch {} could not probe source video: {}", batch_id, e);
+    }
+    let encoder = detect_hardware_encoder(&app, &shared.quality).await;
+
+    // One intro/outro cache for the whole batch so intros/outros are prepared once per aspect ratio
+    let intro_outro_cache = Arc::new(Mutex::new(IntroOutroCache::default()));
+
+    let counters = Arc::new(Mutex::new((0usize, 0usize, 0usize))); // (completed, failed, cancelled)
+    let shared = Arc::new(shared);
+
+    let results: Vec<ClipBuildResult> = stream::iter(clips.into_iter())
+        .map(|clip| {
+            let app = app.clone();
+            let batch_id = batch_id.clone();
+            let shared = shared.clone();
+            let intro_outro_cache = intro_outro_cache.clone();
+            let counters = counters.clone();
+
+            async move {
+                // Skip clips that were cancelled while queued
+                let still_active = ACTIVE_CLIP_BUILDS.lock().unwrap().contains_key(&clip.clip_id);
+
+                let result = if !still_active {
+                    println!("[Rust] Batch {} skipping cancelled clip: {}", batch_id, clip.clip_id);
+                    ClipBuildResult {
+                        clip_id: clip.clip_id.clone(),
+                        project_id: shared.project_id.clone(),
+                        success: false,
+                        output_path: None,
+                        thumbnail_path: None,
+                        duration: None,
+                        file_size: None,
+                        error: Some("Cancelled".to_string()),
+                    }
+                } else {
+                    let build_result = build_clip_internal_simple(
+                        &app,
+                        &shared.project_id,
+                        &clip.clip_id,
+                        &clip.clip_name,
+                        &shared.video_path,
+                        &clip.segments,
+                        shared.subtitle_settings.clone(),
+                        shared.transcript_words.clone(),
+                        shared.transcript_segments.clone(),
+                        shared.max_words,
+                        &shared.aspect_ratios,
+                        &shared.quality,
+                        shared.frame_rate,
+                        &shared.output_format,
+                        shared.run_number,
+                        shared.intro_path.as_deref(),
+                        shared.intro_duration,
+                        shared.outro_path.as_deref(),
+                        shared.outro_duration,
+                        clip.export_options.clone().or_else(|| shared.export_options.clone()),
+                        clip.render_options.clone().or_else(|| shared.render_options.clone()),
+                        intro_outro_cache
+                    ).await;
+
+                    let result = build_result.unwrap_or_else(|e| {
+                        println!("[Rust] Batch {} clip {} failed: {}", batch_id, clip.clip_id, e);
+                        ClipBuildResult {
+                            clip_id: clip.clip_id.clone(),
+                            project_id: shared.project_id.clone(),
+                            success: false,
+                            output_path: None,
+                            thumbnail_path: None,
+                            duration: None,
+                            file_size: None,
+                            error: Some(e),
+                        }
+                    });
+
+                    ACTIVE_CLIP_BUILDS.lock().unwrap().remove(&clip.clip_id);
+
+                    // Per-clip completion uses the same event as single builds
+                    let _ = app.emit("clip-build-complete", &result);
+                    result
+                };
+
+                // Update batch counters and emit batch-level progress
+                let (completed, failed, cancelled) = {
+                    let mut counters = counters.lock().unwrap();
+                    if !still_active {
+                        counters.2 += 1;
+                    } else if result.success {
+                        counters.0 += 1;
+                    } else {
+                        counters.1 += 1;
+                    }
+                    *counters
+                };
+                let done = completed + failed + cancelled;
+
+                let _ = app.emit("clip-batch-progress", ClipBatchProgress {
+                    batch_id: batch_id.clone(),
+                    project_id: shared.project_id.clone(),
+                    total,
+                    completed,
+                    failed,
+                    cancelled,
+                    progress: if total > 0 { done as f64 / total as f64 * 100.0 } else { 100.0 },
+                    current_clip_id: Some(clip.clip_id.clone()),
+                    message: format!("Finished {}/{} clips", done, total),
+                });
+
+                result
+            }
+        })
+        .buffer_unordered(clip_concurrency())
+        .collect()
+        .await;
+
+    let (succeeded, failed, cancelled) = *counters.lock().unwrap();
+    let mut summary = ClipBatchSummary {
+        batch_id: batch_id.clone(),
+        project_id: shared.project_id.clone(),
+        total,
+        succeeded,
+        failed,
+        cancelled,
+        elapsed_seconds: started_at.elapsed().as_secs_f64(),
+        manifest_path: None,
+        results,
+    };
+
+    match write_batch_manifest(&shared, &summary, &encoder.codec) {
+        Ok(path) => summary.manifest_path = Some(path.to_string_lossy().to_string()),
+        Err(e) => println!("[Rust] Failed to write batch manifest: {}", e),
+    }
+
+    println!("[Rust] Batch {} finished: {} succeeded, {} failed, {} cancelled in {:.1}s",
+        batch_id, succeeded, failed, cancelled, summary.elapsed_seconds);
+
+    summary
+}
+
+// Write the batch summary manifest into the run folder used by the batch
+fn write_batch_manifest(
+    shared: &BatchSharedSettings,
+    summary: &ClipBatchSummary,
+    encoder_codec: &str
+) -> Result<std::path::PathBuf, String> {
+    let paths = crate::storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+
+    let project_clips_dir = paths.clips.join(format!("project_{}", shared.project_id));
+    let run_folder = get_or_create_run_folder(&project_clips_dir, shared.run_number)?;
+    let manifest_path = run_folder.join(format!("batch_{}.json", summary.batch_id));
+
+    let manifest = ClipBatchManifest {
+        batch_id: &summary.batch_id,
+        project_id: &shared.project_id,
+        video_path: &shared.video_path,
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        aspect_ratios: &shared.aspect_ratios,
+        quality: &shared.quality,
+        frame_rate: shared.frame_rate,
+        output_format: &shared.output_format,
+        encoder: encoder_codec.to_string(),
+        run_number: shared.run_number,
+        summary,
+    };
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize batch manifest: {}", e))?;
+    std::fs::write(&manifest_path, json)
+        .map_err(|e| format!("Failed to write batch manifest: {}", e))?;
+
+    println!("[Rust] Wrote batch manifest: {}", manifest_path.display());
+    Ok(manifest_path)
+}
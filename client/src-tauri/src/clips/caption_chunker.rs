@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use super::captions::ClipWord;
+use super::script::needs_space_between;
+use super::types::CaptionChunking;
+
+// Groups clip words into caption pages. Pages end at punctuation and pauses, stay inside the
+// word/character/line/duration limits, and never span two clip segments (a jump cut would
+// otherwise show words from both sides of the cut together) or two speakers.
+
+// Trailing punctuation of a word, ignoring closing quotes and brackets
+enum Punctuation {
+    None,
+    // Comma-like: a good place to break once the page has a few words
+    Weak,
+    // Sentence end: always a good place to break
+    Strong,
+}
+
+fn trailing_punctuation(word: &str) -> Punctuation {
+    let trimmed = word.trim().trim_end_matches(['"', '\'', ')', ']', '”', '’', '»']);
+    match trimmed.chars().last() {
+        Some('.' | '!' | '?' | '…' | '。' | '！' | '？') => Punctuation::Strong,
+        Some(',' | ';' | ':' | '–' | '—' | '，' | '、') => Punctuation::Weak,
+        _ => Punctuation::None,
+    }
+}
+
+// Lines needed to set the words with greedy wrapping at max_chars_per_line
+fn lines_needed(words: &[ClipWord], max_chars_per_line: usize) -> usize {
+    if max_chars_per_line == 0 {
+        return 1;
+    }
+    let mut lines = 0;
+    let mut line_chars = 0;
+    for (i, word) in words.iter().enumerate() {
+        let chars = word.word.trim().chars().count();
+        let space = if i > 0 && needs_space_between(&words[i - 1].word, &word.word) { 1 } else { 0 };
+        if lines == 0 || line_chars + space + chars > max_chars_per_line {
+            lines += 1;
+            line_chars = chars;
+        } else {
+            line_chars += space + chars;
+        }
+    }
+    lines
+}
+
+// Whether word i has to start a new page (the current page starts at word start)
+fn must_break_before(words: &[ClipWord], start: usize, i: usize, rules: &CaptionChunking, max_words: usize) -> bool {
+    let previous = &words[i - 1];
+    let page_duration = previous.end - words[start].start;
+
+    words[i].segment_index != previous.segment_index
+        || words[i].speaker != previous.speaker
+        || words[i].start - previous.end >= rules.pause_threshold
+        || i - start >= max_words
+        || (rules.max_lines > 0 && lines_needed(&words[start..=i], rules.max_chars_per_line) > rules.max_lines)
+        || (words[i].end - words[start].start > rules.max_duration && page_duration >= rules.min_duration)
+}
+
+// Whether the page should end after word i (punctuation, once the page has been up long enough)
+fn should_break_after(words: &[ClipWord], start: usize, i: usize, rules: &CaptionChunking) -> bool {
+    if !rules.break_on_punctuation || words[i].end - words[start].start < rules.min_duration {
+        return false;
+    }
+    match trailing_punctuation(&words[i].word) {
+        Punctuation::Strong => true,
+        Punctuation::Weak => i > start,
+        Punctuation::None => false,
+    }
+}
+
+// Split the clip words into pages (ranges into words, in order, covering every word)
+pub fn chunk_words(words: &[ClipWord], rules: &CaptionChunking, max_words: usize) -> Vec<Range<usize>> {
+    let max_words = max_words.max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    for i in 0..words.len() {
+        if i > start && must_break_before(words, start, i, rules, max_words) {
+            chunks.push(start..i);
+            start = i;
+        }
+        if i + 1 < words.len() && should_break_after(words, start, i, rules) {
+            chunks.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+    if start < words.len() {
+        chunks.push(start..words.len());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::test_words::clip_word;
+
+    #[test]
+    fn test_breaks_on_punctuation_and_pauses() {
+        let words = vec![
+            clip_word("Hello", 0.0, 0.5, 0),
+            clip_word("there.", 0.5, 1.0, 0),
+            clip_word("How", 1.0, 1.3, 0),
+            clip_word("are", 1.3, 1.6, 0),
+            // 1s pause
+            clip_word("you", 2.6, 3.0, 0),
+        ];
+        assert_eq!(chunk_words(&words, &CaptionChunking::default(), 4), vec![0..2, 2..4, 4..5]);
+    }
+
+    #[test]
+    fn test_never_spans_segments() {
+        let words = vec![
+            clip_word("one", 0.0, 0.3, 0),
+            clip_word("two", 0.3, 0.6, 0),
+            clip_word("three", 0.6, 0.9, 1),
+        ];
+        assert_eq!(chunk_words(&words, &CaptionChunking::default(), 4), vec![0..2, 2..3]);
+    }
+
+    #[test]
+    fn test_never_spans_speakers() {
+        let mut words = vec![clip_word("yes", 0.0, 0.3, 0), clip_word("no", 0.3, 0.6, 0), clip_word("maybe", 0.6, 0.9, 0)];
+        words[0].speaker = Some("A".to_string());
+        words[1].speaker = Some("B".to_string());
+        words[2].speaker = Some("B".to_string());
+        assert_eq!(chunk_words(&words, &CaptionChunking::default(), 4), vec![0..1, 1..3]);
+    }
+
+    #[test]
+    fn test_respects_word_and_line_limits() {
+        let words: Vec<ClipWord> = (0..6).map(|i| clip_word("abcdefgh", i as f64 * 0.3, i as f64 * 0.3 + 0.3, 0)).collect();
+        let rules = CaptionChunking { max_chars_per_line: 17, max_lines: 1, ..CaptionChunking::default() };
+        assert_eq!(chunk_words(&words, &rules, 4), vec![0..2, 2..4, 4..6]);
+        assert_eq!(chunk_words(&words, &CaptionChunking::default(), 3), vec![0..3, 3..6]);
+    }
+}
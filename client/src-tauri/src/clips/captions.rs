@@ -0,0 +1,361 @@
+use super::caption_chunker::chunk_words;
+use super::script::{join_caption_words, split_caption_tokens};
+use super::types::{CaptionChunking, CaptionTrack, WhisperSegment, WordInfo};
+
+// A transcript word mapped onto the built clip's timeline
+#[derive(Clone, Debug)]
+pub struct ClipWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+    // Index of the clip segment the word came from
+    pub segment_index: usize,
+    pub speaker: Option<String>,
+}
+
+// A caption cue (one "page" of words shown together)
+#[derive(Clone, Debug)]
+pub struct CaptionCue {
+    pub start: f64,
+    pub end: f64,
+    pub words: Vec<ClipWord>,
+}
+
+impl CaptionCue {
+    pub fn text(&self) -> String {
+        join_caption_words(self.words.iter().map(|w| w.word.as_str()))
+    }
+}
+
+// Flatten transcript words into clip-relative times.
+// Each clip segment is appended after the previous one, and time_offset (e.g. the intro
+// duration) is added to every word so captions line up with the final output file.
+pub fn build_clip_timeline(
+    all_words: &[WordInfo],
+    clip_segments: &[serde_json::Value],
+    time_offset: f64
+) -> Vec<ClipWord> {
+    let mut clip_timeline_words: Vec<ClipWord> = Vec::new();
+    let mut current_clip_time = 0.0;
+
+    for (segment_index, clip_segment) in clip_segments.iter().enumerate() {
+        let clip_seg_start = clip_segment["start_time"].as_f64().unwrap_or(0.0);
+        let clip_seg_end = clip_segment["end_time"].as_f64().unwrap_or(0.0);
+        let clip_seg_duration = clip_seg_end - clip_seg_start;
+
+        for word in all_words {
+            // Filter words within this segment
+            // Add buffer to catch boundary words
+            if word.start >= clip_seg_start - 0.1 && word.end <= clip_seg_end + 0.1 {
+                // Calculate relative timing and add the time offset (e.g., intro duration)
+                let start_rel = word.start - clip_seg_start + current_clip_time + time_offset;
+                let end_rel = word.end - clip_seg_start + current_clip_time + time_offset;
+
+                clip_timeline_words.push(ClipWord {
+                    word: word.word.clone(),
+                    start: start_rel,
+                    end: end_rel,
+                    segment_index,
+                    speaker: word.speaker.clone(),
+                });
+            }
+        }
+        current_clip_time += clip_seg_duration;
+    }
+
+    // Sort by start time just in case
+    clip_timeline_words.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap_or(std::cmp::Ordering::Equal));
+    clip_timeline_words
+}
+
+// Source-timeline words inside the clip segments (same boundary buffer as the clip timeline),
+// each word once even where segments overlap
+pub fn words_in_segments(all_words: &[WordInfo], clip_segments: &[serde_json::Value]) -> Vec<WordInfo> {
+    all_words
+        .iter()
+        .filter(|word| {
+            clip_segments.iter().any(|segment| {
+                let start = segment["start_time"].as_f64().unwrap_or(0.0);
+                let end = segment["end_time"].as_f64().unwrap_or(0.0);
+                word.start >= start - 0.1 && word.end <= end + 0.1
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+// Words with a missing speaker label take the label of the segment they fall in
+pub fn label_speakers(words: &[WordInfo], segments: &[WhisperSegment]) -> Vec<WordInfo> {
+    words
+        .iter()
+        .map(|word| {
+            let mut word = word.clone();
+            if word.speaker.is_none() {
+                let midpoint = (word.start + word.end) / 2.0;
+                word.speaker = segments
+                    .iter()
+                    .find(|s| midpoint >= s.start && midpoint <= s.end)
+                    .and_then(|s| s.speaker.clone());
+            }
+            word
+        })
+        .collect()
+}
+
+// Source-timeline words of a caption track. Segment-timed tracks (e.g. translations) get each
+// segment's duration spread over its words by length.
+pub fn track_words(track: &CaptionTrack) -> Vec<WordInfo> {
+    if !track.words.is_empty() {
+        return track.words.clone();
+    }
+
+    let mut words = Vec::new();
+    for segment in &track.segments {
+        if let Some(segment_words) = segment.words.as_ref().filter(|w| !w.is_empty()) {
+            words.extend(label_speakers(segment_words, std::slice::from_ref(segment)));
+            continue;
+        }
+        words.extend(interpolate_words(split_caption_tokens(&segment.text), segment.start, segment.end, segment.speaker.as_deref()));
+    }
+    words
+}
+
+// Words for text with only a start and end time: the span is shared out by token length
+pub fn interpolate_words(tokens: Vec<String>, start: f64, end: f64, speaker: Option<&str>) -> Vec<WordInfo> {
+    let total_chars: usize = tokens.iter().map(|t| t.chars().count().max(1)).sum();
+    let mut cursor = start;
+    tokens
+        .into_iter()
+        .map(|token| {
+            let share = token.chars().count().max(1) as f64 / total_chars as f64;
+            let word_end = cursor + (end - start) * share;
+            let word = WordInfo { word: token, start: cursor, end: word_end, confidence: None, speaker: speaker.map(|s| s.to_string()) };
+            cursor = word_end;
+            word
+        })
+        .collect()
+}
+
+// Group clip words into cues (same paging as the burned captions)
+pub fn group_into_cues(words: &[ClipWord], chunking: &CaptionChunking, max_words: usize) -> Vec<CaptionCue> {
+    chunk_words(words, chunking, max_words)
+        .into_iter()
+        .map(|range| &words[range])
+        .map(|chunk| CaptionCue {
+            start: chunk.first().map(|w| w.start).unwrap_or(0.0),
+            end: chunk.last().map(|w| w.end).unwrap_or(0.0),
+            words: chunk.to_vec(),
+        })
+        .collect()
+}
+
+// Format seconds as an SRT timestamp (HH:MM:SS,mmm)
+pub fn format_srt_time(t: f64) -> String {
+    let (hours, mins, secs, millis) = split_time(t);
+    format!("{:02}:{:02}:{:02},{:03}", hours, mins, secs, millis)
+}
+
+// Format seconds as a WebVTT timestamp (HH:MM:SS.mmm)
+pub fn format_vtt_time(t: f64) -> String {
+    let (hours, mins, secs, millis) = split_time(t);
+    format!("{:02}:{:02}:{:02}.{:03}", hours, mins, secs, millis)
+}
+
+fn split_time(t: f64) -> (u64, u64, u64, u64) {
+    let total_millis = (t.max(0.0) * 1000.0).round() as u64;
+    (
+        total_millis / 3_600_000,
+        (total_millis % 3_600_000) / 60_000,
+        (total_millis % 60_000) / 1000,
+        total_millis % 1000,
+    )
+}
+
+// Render cues as an SRT document
+pub fn to_srt(cues: &[CaptionCue]) -> String {
+    let mut out = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            format_srt_time(cue.start),
+            format_srt_time(cue.end),
+            cue.text()
+        ));
+    }
+    out
+}
+
+// Escape text for WebVTT cue payloads and TTML
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// Cue text with a WebVTT timestamp tag before every word spoken after the cue starts,
+// so players can highlight words karaoke-style
+fn vtt_cue_payload(cue: &CaptionCue) -> String {
+    cue.words
+        .iter()
+        .map(|w| {
+            let word = format!("<c>{}</c>", escape_markup(w.word.trim()));
+            if w.start > cue.start + 0.0005 && w.start < cue.end {
+                format!("<{}>{}", format_vtt_time(w.start), word)
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Render cues as a WebVTT document (with word-level timestamps)
+pub fn to_vtt(cues: &[CaptionCue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_vtt_time(cue.start),
+            format_vtt_time(cue.end),
+            vtt_cue_payload(cue)
+        ));
+    }
+    out
+}
+
+// Render cues as a TTML document (clock times use the same HH:MM:SS.mmm form as WebVTT).
+// xml:lang is only written when the language is known.
+pub fn to_ttml(cues: &[CaptionCue], language: Option<&str>) -> String {
+    let lang_attribute = language
+        .map(|language| format!(" xml:lang=\"{}\"", escape_markup(language).replace('"', "&quot;")))
+        .unwrap_or_default();
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tt xmlns=\"http://www.w3.org/ns/ttml\"{}>\n  <body>\n    <div>\n",
+        lang_attribute
+    );
+    for cue in cues {
+        out.push_str(&format!(
+            "      <p begin=\"{}\" end=\"{}\">{}</p>\n",
+            format_vtt_time(cue.start),
+            format_vtt_time(cue.end),
+            escape_markup(&cue.text())
+        ));
+    }
+    out.push_str("    </div>\n  </body>\n</tt>\n");
+    out
+}
+
+// File extension for a caption format name ("srt", "vtt"/"webvtt", "ttml"/"dfxp")
+pub fn caption_file_extension(format: &str) -> Option<&'static str> {
+    match format.to_lowercase().as_str() {
+        "srt" => Some("srt"),
+        "vtt" | "webvtt" => Some("vtt"),
+        "ttml" | "dfxp" | "xml" => Some("ttml"),
+        _ => None,
+    }
+}
+
+// Render cues in the given caption format
+pub fn render_caption_document(cues: &[CaptionCue], format: &str, language: Option<&str>) -> Option<String> {
+    match caption_file_extension(format)? {
+        "srt" => Some(to_srt(cues)),
+        "vtt" => Some(to_vtt(cues)),
+        _ => Some(to_ttml(cues, language)),
+    }
+}
+
+// Caption cues for a clip (same timing and paging as the burned captions)
+pub fn clip_caption_cues(
+    words: &[WordInfo],
+    clip_segments: &[serde_json::Value],
+    chunking: &CaptionChunking,
+    max_words: usize,
+    time_offset: f64
+) -> Vec<CaptionCue> {
+    let timeline = build_clip_timeline(words, clip_segments, time_offset);
+    group_into_cues(&timeline, chunking, max_words)
+}
+
+// Write caption cues (see clip_caption_cues) to a caption file
+pub fn export_caption_file(
+    cues: &[CaptionCue],
+    format: &str,
+    language: Option<&str>,
+    output_path: &std::path::Path
+) -> Result<(), String> {
+    let content = render_caption_document(cues, format, language)
+        .ok_or_else(|| format!("Unsupported caption format: {}", format))?;
+    std::fs::write(output_path, content)
+        .map_err(|e| format!("Failed to write caption file {}: {}", output_path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::test_words::word;
+
+    #[test]
+    fn test_build_clip_timeline_joins_segments() {
+        let words = vec![word("hello", 10.0, 10.5), word("skipped", 12.0, 12.5), word("world", 20.2, 20.6)];
+        let segments = vec![
+            serde_json::json!({ "start_time": 10.0, "end_time": 11.0 }),
+            serde_json::json!({ "start_time": 20.0, "end_time": 21.0 }),
+        ];
+
+        let timeline = build_clip_timeline(&words, &segments, 2.0);
+        assert_eq!(timeline.len(), 2);
+        assert!((timeline[0].start - 2.0).abs() < 1e-9);
+        assert!((timeline[1].start - 3.2).abs() < 1e-9);
+
+        // The manifest keeps the same words on the source timeline
+        let clip_words = words_in_segments(&words, &segments);
+        assert_eq!(clip_words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>(), vec!["hello", "world"]);
+        assert!((clip_words[1].start - 20.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_format_times() {
+        assert_eq!(format_srt_time(3723.456), "01:02:03,456");
+        assert_eq!(format_vtt_time(0.5), "00:00:00.500");
+    }
+
+    #[test]
+    fn test_to_srt() {
+        let words = vec![
+            ClipWord { word: "one".to_string(), start: 0.0, end: 0.4, segment_index: 0, speaker: None },
+            ClipWord { word: "two".to_string(), start: 0.4, end: 0.8, segment_index: 0, speaker: None },
+            ClipWord { word: "three".to_string(), start: 0.8, end: 1.2, segment_index: 0, speaker: None },
+        ];
+        let srt = to_srt(&group_into_cues(&words, &CaptionChunking::default(), 2));
+        assert_eq!(srt, "1\n00:00:00,000 --> 00:00:00,800\none two\n\n2\n00:00:00,800 --> 00:00:01,200\nthree\n\n");
+    }
+
+    #[test]
+    fn test_segment_timed_track_words() {
+        let track = CaptionTrack {
+            language: "es".to_string(),
+            words: Vec::new(),
+            segments: vec![WhisperSegment { id: 0, start: 1.0, end: 2.0, text: "hola amigo".to_string(), words: None, speaker: None }],
+        };
+        let words = track_words(&track);
+        assert_eq!(words.len(), 2);
+        assert!((words[0].end - (1.0 + 4.0 / 9.0)).abs() < 1e-9);
+        assert!((words[1].end - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_vtt_word_timestamps_and_ttml() {
+        let words = vec![
+            ClipWord { word: "rock".to_string(), start: 0.0, end: 0.4, segment_index: 0, speaker: None },
+            ClipWord { word: "&roll".to_string(), start: 0.5, end: 0.8, segment_index: 0, speaker: None },
+        ];
+        let cues = group_into_cues(&words, &CaptionChunking::default(), 4);
+        assert_eq!(
+            to_vtt(&cues),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:00.800\n<c>rock</c> <00:00:00.500><c>&amp;roll</c>\n\n"
+        );
+        let ttml = to_ttml(&cues, Some("pt-BR"));
+        assert!(ttml.contains("<tt xmlns=\"http://www.w3.org/ns/ttml\" xml:lang=\"pt-BR\">"));
+        assert!(ttml.contains("<p begin=\"00:00:00.000\" end=\"00:00:00.800\">rock &amp;roll</p>"));
+        assert!(to_ttml(&cues, None).contains("<tt xmlns=\"http://www.w3.org/ns/ttml\">"));
+    }
+}
@@ -0,0 +1,148 @@
+use super::script::split_punctuation;
+use super::types::{CensorAudioMode, CensorCaptionMode, CensorOptions, WordInfo};
+
+// Profanity censoring from word timings: matched transcript words are masked in the caption
+// text, and their time ranges are bleeped or muted in the clip audio.
+
+// Whether the word is on the list (case-insensitive, ignoring punctuation; "word*" matches any ending)
+pub fn is_censored(word: &str, list: &[String]) -> bool {
+    let (_, core, _) = split_punctuation(word);
+    if core.is_empty() {
+        return false;
+    }
+    let core = core.to_lowercase();
+    list.iter().any(|entry| {
+        let entry = entry.trim().to_lowercase();
+        match entry.strip_suffix('*') {
+            Some(prefix) => !prefix.is_empty() && core.starts_with(prefix),
+            None => !entry.is_empty() && core == entry,
+        }
+    })
+}
+
+// Caption text for a censored word, keeping its punctuation ("fuck," -> "f**k,")
+pub fn mask_word(word: &str, options: &CensorOptions) -> String {
+    let (leading, core, trailing) = split_punctuation(word);
+    let masked = match options.caption_mode {
+        CensorCaptionMode::None => return word.to_string(),
+        CensorCaptionMode::Emoji => options.replacement.clone(),
+        CensorCaptionMode::Mask => {
+            let chars: Vec<char> = core.chars().collect();
+            chars
+                .iter()
+                .enumerate()
+                .map(|(i, c)| if i == 0 || (i == chars.len() - 1 && chars.len() > 3) { *c } else { '*' })
+                .collect()
+        }
+    };
+    format!("{}{}{}", leading, masked, trailing)
+}
+
+// Transcript words with censored words masked for captions (timings unchanged)
+pub fn censor_caption_words(words: &[WordInfo], options: &CensorOptions) -> Vec<WordInfo> {
+    words
+        .iter()
+        .map(|word| {
+            if is_censored(&word.word, &options.words) {
+                WordInfo { word: mask_word(&word.word, options), ..word.clone() }
+            } else {
+                word.clone()
+            }
+        })
+        .collect()
+}
+
+// Source-time ranges to bleep or mute: censored words plus padding, overlapping ranges merged
+pub fn censored_intervals(words: &[WordInfo], options: &CensorOptions) -> Vec<(f64, f64)> {
+    if options.audio_mode == CensorAudioMode::None {
+        return Vec::new();
+    }
+    let padding = options.padding_ms as f64 / 1000.0;
+    let mut intervals: Vec<(f64, f64)> = words
+        .iter()
+        .filter(|word| is_censored(&word.word, &options.words))
+        .map(|word| ((word.start - padding).max(0.0), word.end + padding))
+        .collect();
+    intervals.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// Audio filter (-af) censoring the source range start..end, with times relative to the range start.
+// None when nothing in the range is censored.
+pub fn audio_censor_filter(intervals: &[(f64, f64)], options: &CensorOptions, start: f64, end: f64) -> Option<String> {
+    let conditions: Vec<String> = intervals
+        .iter()
+        .filter(|(a, b)| *b > start && *a < end)
+        .map(|(a, b)| format!("between(t,{:.3},{:.3})", (a - start).max(0.0), (b.min(end) - start)))
+        .collect();
+    if conditions.is_empty() {
+        return None;
+    }
+    let condition = conditions.join("+");
+    let mute = format!("volume=enable='{}':volume=0", condition);
+
+    match options.audio_mode {
+        CensorAudioMode::None => None,
+        CensorAudioMode::Mute => Some(mute),
+        // The tone is gated to the same ranges and mixed over the muted audio
+        CensorAudioMode::Bleep => Some(format!(
+            "{}[censored];sine=frequency={}:sample_rate=48000:duration={:.3},volume='0.3*({})':eval=frame[bleep];[censored][bleep]amix=inputs=2:duration=first:normalize=0",
+            mute,
+            options.bleep_frequency,
+            end - start,
+            condition
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::test_words::word;
+
+    fn options(words: &[&str]) -> CensorOptions {
+        CensorOptions { words: words.iter().map(|w| w.to_string()).collect(), ..CensorOptions::default() }
+    }
+
+    #[test]
+    fn test_matching_and_masking() {
+        let list = options(&["fuck*", "shit"]);
+        assert!(is_censored("Fucking,", &list.words));
+        assert!(is_censored("\"shit!\"", &list.words));
+        assert!(!is_censored("shitake", &list.words));
+        assert_eq!(mask_word("Fuck,", &list), "F**k,");
+        assert_eq!(mask_word("ass", &list), "a**");
+        let emoji = CensorOptions { caption_mode: CensorCaptionMode::Emoji, ..list };
+        assert_eq!(mask_word("shit!", &emoji), "🤬!");
+    }
+
+    #[test]
+    fn test_intervals_are_padded_and_merged() {
+        let words = vec![word("damn", 1.0, 1.2), word("hell", 1.25, 1.5), word("ok", 2.0, 2.2), word("damn", 3.0, 3.4)];
+        let intervals = censored_intervals(&words, &options(&["damn", "hell"]));
+        assert_eq!(intervals.len(), 2);
+        assert!((intervals[0].0 - 0.95).abs() < 1e-9 && (intervals[0].1 - 1.55).abs() < 1e-9);
+        assert!((intervals[1].0 - 2.95).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_audio_filter_is_relative_to_range() {
+        let mute = CensorOptions { audio_mode: CensorAudioMode::Mute, ..options(&[]) };
+        let intervals = vec![(10.5, 11.0), (30.0, 31.0)];
+        assert_eq!(
+            audio_censor_filter(&intervals, &mute, 10.0, 20.0).as_deref(),
+            Some("volume=enable='between(t,0.500,1.000)':volume=0")
+        );
+        assert_eq!(audio_censor_filter(&intervals, &mute, 12.0, 20.0), None);
+        let bleep = audio_censor_filter(&intervals, &options(&[]), 10.0, 20.0).unwrap();
+        assert!(bleep.contains("sine=frequency=1000") && bleep.ends_with("amix=inputs=2:duration=first:normalize=0"));
+    }
+}
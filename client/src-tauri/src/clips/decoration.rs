@@ -0,0 +1,141 @@
+use regex::Regex;
+
+use super::script::split_punctuation;
+use super::subtitle::{to_ass_override_color, wrap_emoji_runs};
+use super::types::CaptionDecorationRule;
+
+// Rule-driven caption decoration: keywords and patterns from the subtitle settings pick out
+// words that get recolored, scaled, uppercased, or followed by an emoji in the burned captions.
+
+// What to do with one matched word
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordDecoration {
+    // ASS override color (e.g. "&H00FFFF&")
+    pub color: Option<String>,
+    pub scale: Option<f32>,
+    pub uppercase: bool,
+    pub emoji: Option<String>,
+}
+
+// Decoration rules with their patterns compiled
+pub struct CaptionDecorator {
+    rules: Vec<(CaptionDecorationRule, Option<Regex>)>,
+}
+
+impl CaptionDecorator {
+    pub fn new(rules: &[CaptionDecorationRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let pattern = match rule.pattern.as_deref().filter(|p| !p.is_empty()) {
+                    Some(pattern) => Some(
+                        Regex::new(&format!("(?i){}", pattern))
+                            .map_err(|e| format!("Failed to compile caption decoration pattern '{}': {}", pattern, e))?,
+                    ),
+                    None => None,
+                };
+                Ok((rule.clone(), pattern))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // Decoration of the first rule matching the word
+    pub fn match_word(&self, word: &str) -> Option<WordDecoration> {
+        let (_, core, _) = split_punctuation(word);
+        let core = core.to_lowercase();
+        self.rules
+            .iter()
+            .find(|(rule, pattern)| {
+                (!core.is_empty() && rule.keywords.iter().any(|k| k.trim().to_lowercase() == core))
+                    || pattern.as_ref().map(|p| p.is_match(word.trim())).unwrap_or(false)
+            })
+            .map(|(rule, _)| WordDecoration {
+                color: rule.color.as_deref().map(to_ass_override_color),
+                scale: rule.scale.filter(|s| *s > 0.0 && (*s - 100.0).abs() > f32::EPSILON),
+                uppercase: rule.uppercase,
+                emoji: rule.emoji.clone().filter(|e| !e.trim().is_empty()),
+            })
+    }
+}
+
+// Visible text of a word after decoration (what the line layout measures)
+pub fn decorated_text(word: &str, decoration: Option<&WordDecoration>) -> String {
+    let Some(decoration) = decoration else {
+        return word.to_string();
+    };
+    let text = if decoration.uppercase { word.to_uppercase() } else { word.to_string() };
+    match &decoration.emoji {
+        Some(emoji) => format!("{} {}", text, emoji.trim()),
+        None => text,
+    }
+}
+
+// ASS text of a word: decoration tags around the text (reset afterwards to the base color and
+// scale), with emoji switched to the emoji font
+pub fn decorated_ass_text(word: &str, decoration: Option<&WordDecoration>, font_family: &str, base_color: &str) -> String {
+    let text = wrap_emoji_runs(&decorated_text(word, decoration), font_family);
+    let Some(decoration) = decoration else {
+        return text;
+    };
+
+    let mut tags = String::new();
+    let mut resets = String::new();
+    if let Some(color) = &decoration.color {
+        tags.push_str(&format!("\\1c{}", color));
+        resets.push_str(&format!("\\1c{}", base_color));
+    }
+    if let Some(scale) = decoration.scale {
+        tags.push_str(&format!("\\fscx{:.0}\\fscy{:.0}", scale, scale));
+        resets.push_str("\\fscx100\\fscy100");
+    }
+    if tags.is_empty() {
+        return text;
+    }
+    format!("{{{}}}{}{{{}}}", tags, text, resets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(keywords: &[&str], pattern: Option<&str>) -> CaptionDecorationRule {
+        CaptionDecorationRule {
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            pattern: pattern.map(|p| p.to_string()),
+            ..CaptionDecorationRule::default()
+        }
+    }
+
+    #[test]
+    fn test_keyword_and_pattern_matching() {
+        let decorator = CaptionDecorator::new(&[
+            CaptionDecorationRule { emoji: Some("💰".to_string()), ..rule(&["money"], None) },
+            CaptionDecorationRule { uppercase: true, ..rule(&[], Some(r"^\$?\d+")) },
+        ]).unwrap();
+        assert!(decorator.match_word("Money!").unwrap().emoji.is_some());
+        assert!(decorator.match_word("$100").unwrap().uppercase);
+        assert_eq!(decorator.match_word("moneys"), None);
+        assert!(CaptionDecorator::new(&[rule(&[], Some("("))]).is_err());
+    }
+
+    #[test]
+    fn test_decorated_ass_text() {
+        let decoration = WordDecoration {
+            color: Some("&H00FFFF&".to_string()),
+            scale: Some(130.0),
+            uppercase: true,
+            emoji: Some("🔥".to_string()),
+        };
+        assert_eq!(decorated_text("huge,", Some(&decoration)), "HUGE, 🔥");
+        assert_eq!(
+            decorated_ass_text("huge,", Some(&decoration), "Montserrat", "&HFFFFFF&"),
+            "{\\1c&H00FFFF&\\fscx130\\fscy130}HUGE, {\\fnNoto Emoji}🔥{\\fnMontserrat}{\\1c&HFFFFFF&\\fscx100\\fscy100}"
+        );
+        assert_eq!(decorated_ass_text("plain", None, "Montserrat", "&HFFFFFF&"), "plain");
+    }
+}
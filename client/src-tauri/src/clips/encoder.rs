@@ -1,5 +1,10 @@
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
 use tauri_plugin_shell::ShellExt;
 
+// Cached `ffmpeg -encoders` output so repeated and batch builds only probe the sidecar once
+static ENCODER_LIST_CACHE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
 // Helper function to get FFmpeg quality settings
 pub fn get_quality_settings(quality: &str) -> (&str, &str) {
     match quality {
@@ -19,60 +24,75 @@ pub struct EncoderConfig {
     pub quality_value: String,
 }
 
+// Get the list of encoders supported by the bundled FFmpeg (probed once per app run)
+async fn get_available_encoders(app: &tauri::AppHandle) -> Option<String> {
+    {
+        let cache = ENCODER_LIST_CACHE.lock().unwrap();
+        if let Some(encoders) = cache.as_ref() {
+            return Some(encoders.clone());
+        }
+    }
+
+    let shell = app.shell();
+    let output = shell.sidecar("ffmpeg")
+        .ok()?
+        .args(["-encoders"])
+        .output()
+        .await
+        .ok()?;
+
+    let encoders = String::from_utf8_lossy(&output.stdout).to_string();
+    {
+        let mut cache = ENCODER_LIST_CACHE.lock().unwrap();
+        *cache = Some(encoders.clone());
+    }
+    Some(encoders)
+}
+
 // Detect available hardware encoders and return optimal encoder config
 pub async fn detect_hardware_encoder(app: &tauri::AppHandle, quality: &str) -> EncoderConfig {
-    let shell = app.shell();
-    
     // Try to get ffmpeg encoder list
-    let encoder_check = shell.sidecar("ffmpeg")
-        .map_err(|_| ())
-        .and_then(|cmd| Ok(cmd.args(["-encoders"])));
-    
-    if let Ok(cmd) = encoder_check {
-        if let Ok(output) = cmd.output().await {
-            let encoders = String::from_utf8_lossy(&output.stdout);
-            
-            // Check for NVIDIA NVENC (best quality/speed)
-            if encoders.contains("h264_nvenc") {
-                println!("[Rust] Hardware encoder detected: NVIDIA NVENC");
-                let (_, crf) = get_quality_settings(quality);
-                return EncoderConfig {
-                    codec: "h264_nvenc".to_string(),
-                    preset: Some("p4".to_string()), // p4 = medium quality preset
-                    quality_param: "-cq".to_string(),
-                    quality_value: crf.to_string(), // NVENC uses same CRF values
-                };
-            }
-            
-            // Check for Intel Quick Sync
-            if encoders.contains("h264_qsv") {
-                println!("[Rust] Hardware encoder detected: Intel Quick Sync");
-                let (_, crf) = get_quality_settings(quality);
-                return EncoderConfig {
-                    codec: "h264_qsv".to_string(),
-                    preset: None,
-                    quality_param: "-global_quality".to_string(),
-                    quality_value: crf.to_string(),
-                };
-            }
-            
-            // Check for Apple VideoToolbox (macOS)
-            if encoders.contains("h264_videotoolbox") {
-                println!("[Rust] Hardware encoder detected: Apple VideoToolbox");
-                // VideoToolbox uses different quality scale, map CRF to bitrate
-                let quality_value = match quality {
-                    "low" => "2000000",   // 2 Mbps
-                    "medium" => "5000000", // 5 Mbps
-                    "high" => "10000000",  // 10 Mbps
-                    _ => "5000000",
-                };
-                return EncoderConfig {
-                    codec: "h264_videotoolbox".to_string(),
-                    preset: None,
-                    quality_param: "-b:v".to_string(),
-                    quality_value: quality_value.to_string(),
-                };
-            }
+    if let Some(encoders) = get_available_encoders(app).await {
+        // Check for NVIDIA NVENC (best quality/speed)
+        if encoders.contains("h264_nvenc") {
+            println!("[Rust] Hardware encoder detected: NVIDIA NVENC");
+            let (_, crf) = get_quality_settings(quality);
+            return EncoderConfig {
+                codec: "h264_nvenc".to_string(),
+                preset: Some("p4".to_string()), // p4 = medium quality preset
+                quality_param: "-cq".to_string(),
+                quality_value: crf.to_string(), // NVENC uses same CRF values
+            };
+        }
+        
+        // Check for Intel Quick Sync
+        if encoders.contains("h264_qsv") {
+            println!("[Rust] Hardware encoder detected: Intel Quick Sync");
+            let (_, crf) = get_quality_settings(quality);
+            return EncoderConfig {
+                codec: "h264_qsv".to_string(),
+                preset: None,
+                quality_param: "-global_quality".to_string(),
+                quality_value: crf.to_string(),
+            };
+        }
+        
+        // Check for Apple VideoToolbox (macOS)
+        if encoders.contains("h264_videotoolbox") {
+            println!("[Rust] Hardware encoder detected: Apple VideoToolbox");
+            // VideoToolbox uses different quality scale, map CRF to bitrate
+            let quality_value = match quality {
+                "low" => "2000000",   // 2 Mbps
+                "medium" => "5000000", // 5 Mbps
+                "high" => "10000000",  // 10 Mbps
+                _ => "5000000",
+            };
+            return EncoderConfig {
+                codec: "h264_videotoolbox".to_string(),
+                preset: None,
+                quality_param: "-b:v".to_string(),
+                quality_value: quality_value.to_string(),
+            };
         }
     }
     
@@ -1,5 +1,7 @@
 use tauri::Manager;
 
+use super::font_registry::user_fonts_dir;
+
 // Helper to get fonts directory and create fontconfig
 pub fn get_fonts_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     // In dev mode, fonts are in src-tauri/fonts
@@ -42,16 +44,23 @@ pub fn create_fontconfig_file(fonts_dir: &std::path::Path) -> Result<(), String>
     
     // Convert Windows path to Unix-style path for fontconfig
     let fonts_dir_str = fonts_dir.to_string_lossy().replace("\\", "/");
+
+    // User-installed fonts are searched too (they are also embedded in the subtitle files)
+    let user_fonts_entry = match user_fonts_dir() {
+        Ok(dir) => format!("\n    <dir>{}</dir>", dir.to_string_lossy().replace("\\", "/")),
+        Err(_) => String::new(),
+    };
     
     let fontconfig_content = format!(
         r#"<?xml version="1.0"?>
 <!DOCTYPE fontconfig SYSTEM "fonts.dtd">
 <fontconfig>
-    <dir>{}</dir>
+    <dir>{}</dir>{}
     <cachedir>{}/font-cache</cachedir>
 </fontconfig>
 "#,
         fonts_dir_str,
+        user_fonts_entry,
         storage_paths.temp.to_string_lossy().replace("\\", "/")
     );
     
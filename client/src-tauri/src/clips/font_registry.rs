@@ -0,0 +1,246 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use ttf_parser::{name_id, Face};
+
+// Fonts available to subtitles: the bundled fonts directory plus fonts the user installed into
+// storage. Family, weight and style come from the font tables, not the file names.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontFace {
+    pub family: String,
+    pub weight: u32,
+    pub italic: bool,
+    pub path: PathBuf,
+    // Installed by the user (as opposed to bundled with the app)
+    pub user: bool,
+}
+
+// A requested font that is not available as asked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFont {
+    pub family: String,
+    pub weight: u32,
+    pub italic: bool,
+    // Face used instead (nearest weight/style of the family), None if the family is missing
+    pub substitute: Option<FontFace>,
+}
+
+#[derive(Debug, Default)]
+pub struct FontRegistry {
+    faces: Vec<FontFace>,
+}
+
+// Registry per set of scanned directories, rescanned after a font is installed
+type CachedRegistry = (Vec<PathBuf>, Arc<FontRegistry>);
+static REGISTRY: Lazy<Mutex<Option<CachedRegistry>>> = Lazy::new(|| Mutex::new(None));
+
+// Where user-supplied fonts are stored
+pub fn user_fonts_dir() -> Result<PathBuf, String> {
+    let paths = crate::storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let dir = paths.assets.join("fonts");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create user fonts directory: {}", e))?;
+    Ok(dir)
+}
+
+// Registry for the bundled fonts directory and the user fonts directory (cached)
+pub fn font_registry(bundled_dir: Option<&Path>) -> Arc<FontRegistry> {
+    let mut dirs: Vec<(PathBuf, bool)> = bundled_dir.map(|dir| (dir.to_path_buf(), false)).into_iter().collect();
+    match user_fonts_dir() {
+        Ok(dir) => dirs.push((dir, true)),
+        Err(e) => println!("[Rust] WARNING: {}", e),
+    }
+    let key: Vec<PathBuf> = dirs.iter().map(|(dir, _)| dir.clone()).collect();
+
+    let mut cached = REGISTRY.lock().unwrap();
+    if let Some((cached_key, registry)) = cached.as_ref() {
+        if *cached_key == key {
+            return registry.clone();
+        }
+    }
+    let registry = Arc::new(FontRegistry::scan(&dirs));
+    println!("[Rust] Font registry: {} faces", registry.faces.len());
+    *cached = Some((key, registry.clone()));
+    registry
+}
+
+// Forget the cached registry (after fonts were added or removed)
+pub fn invalidate_font_registry() {
+    *REGISTRY.lock().unwrap() = None;
+}
+
+// Read family/weight/style of a font file
+pub fn read_font_face(path: &Path, user: bool) -> Result<FontFace, String> {
+    let data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read font {}: {}", path.display(), e))?;
+    let face = Face::parse(&data, 0)
+        .map_err(|e| format!("Failed to parse font {}: {}", path.display(), e))?;
+
+    // The typographic family groups all weights ("Montserrat"); the legacy family may include
+    // the weight ("Montserrat Black")
+    let name = |id: u16| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == id && name.is_unicode())
+            .find_map(|name| name.to_string())
+    };
+    let family = name(name_id::TYPOGRAPHIC_FAMILY)
+        .or_else(|| name(name_id::FAMILY))
+        .ok_or_else(|| format!("Font {} has no family name", path.display()))?;
+
+    Ok(FontFace {
+        family,
+        weight: face.weight().to_number() as u32,
+        italic: face.is_italic() || face.is_oblique(),
+        path: path.to_path_buf(),
+        user,
+    })
+}
+
+impl FontRegistry {
+    // Scan directories for .ttf/.otf files (unreadable files are skipped)
+    pub fn scan(dirs: &[(PathBuf, bool)]) -> Self {
+        let mut faces = Vec::new();
+        for (dir, user) in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.extension()
+                        .map(|ext| ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf"))
+                        .unwrap_or(false)
+                })
+                .collect();
+            paths.sort();
+            for path in paths {
+                match read_font_face(&path, *user) {
+                    Ok(face) => faces.push(face),
+                    Err(e) => println!("[Rust] Skipping font: {}", e),
+                }
+            }
+        }
+        Self { faces }
+    }
+
+    pub fn faces(&self) -> &[FontFace] {
+        &self.faces
+    }
+
+    // Face for a family at the nearest available weight, preferring the requested style.
+    // Ties follow CSS: bold requests pick the heavier face, regular requests the lighter one.
+    // User fonts win over bundled ones with the same family, weight and style.
+    pub fn resolve(&self, family: &str, weight: u32, italic: bool) -> Option<&FontFace> {
+        let family_faces = self.family_faces(family);
+        let styled: Vec<&FontFace> = family_faces.iter().copied().filter(|face| face.italic == italic).collect();
+        let candidates = if styled.is_empty() { family_faces } else { styled };
+
+        candidates.into_iter().min_by_key(|face| {
+            let distance = (face.weight as i64 - weight as i64).unsigned_abs();
+            let wrong_direction = if weight > 500 { face.weight < weight } else { face.weight > weight };
+            (distance, wrong_direction, !face.user)
+        })
+    }
+
+    // Every face of a family, bundled and user
+    pub fn family_faces(&self, family: &str) -> Vec<&FontFace> {
+        self.faces.iter().filter(|face| face.family.eq_ignore_ascii_case(family.trim())).collect()
+    }
+
+    // Report a requested font that would be substituted or is missing entirely
+    pub fn check(&self, family: &str, weight: u32, italic: bool) -> Option<MissingFont> {
+        let resolved = self.resolve(family, weight, italic);
+        if resolved.map(|face| face.weight == weight && face.italic == italic).unwrap_or(false) {
+            return None;
+        }
+        Some(MissingFont {
+            family: family.to_string(),
+            weight,
+            italic,
+            substitute: resolved.cloned(),
+        })
+    }
+}
+
+// Copy a font file into the user fonts directory after checking it can be read
+pub fn install_user_font(source_path: &Path) -> Result<FontFace, String> {
+    read_font_face(source_path, true)?;
+    let file_name = source_path
+        .file_name()
+        .ok_or_else(|| format!("Invalid font path: {}", source_path.display()))?;
+    let destination = user_fonts_dir()?.join(file_name);
+    std::fs::copy(source_path, &destination)
+        .map_err(|e| format!("Failed to copy font: {}", e))?;
+    invalidate_font_registry();
+    println!("[Rust] Installed user font: {}", destination.display());
+    read_font_face(&destination, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled_registry() -> FontRegistry {
+        FontRegistry::scan(&[(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fonts"), false)])
+    }
+
+    fn file_name(face: Option<&FontFace>) -> String {
+        face.and_then(|f| f.path.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_resolves_from_font_tables() {
+        let registry = bundled_registry();
+        assert_eq!(file_name(registry.resolve("Montserrat", 800, false)), "Montserrat-ExtraBold.ttf");
+        assert_eq!(file_name(registry.resolve("montserrat", 700, true)), "Montserrat-BoldItalic.ttf");
+        assert_eq!(file_name(registry.resolve("Roboto Condensed", 400, false)), "Roboto_Condensed-Regular.ttf");
+        assert!(registry.resolve("Open Sans", 400, false).is_none());
+    }
+
+    #[test]
+    fn test_bundled_emoji_font_resolves() {
+        use super::super::subtitle::{EMOJI_FONT_FAMILY, EMOJI_FONT_FILE};
+        assert_eq!(
+            file_name(bundled_registry().resolve(EMOJI_FONT_FAMILY, 400, false)),
+            EMOJI_FONT_FILE,
+            "fonts/{} is downloaded by build.rs, see fonts/README.md",
+            EMOJI_FONT_FILE
+        );
+    }
+
+    #[test]
+    fn test_build_downloads_emoji_font() {
+        use super::super::subtitle::EMOJI_FONT_FILE;
+        let build_script = include_str!("../../build.rs");
+        assert!(build_script.contains(&format!("\"{}\"", EMOJI_FONT_FILE)));
+    }
+
+    #[test]
+    fn test_nearest_weight_and_missing_report() {
+        let registry = FontRegistry {
+            faces: [(400, false), (700, false), (400, true)]
+                .iter()
+                .map(|&(weight, italic)| FontFace {
+                    family: "Test".to_string(),
+                    weight,
+                    italic,
+                    path: PathBuf::from(format!("Test-{}-{}.ttf", weight, italic)),
+                    user: false,
+                })
+                .collect(),
+        };
+        assert_eq!(registry.resolve("Test", 600, false).map(|f| f.weight), Some(700));
+        assert_eq!(registry.resolve("Test", 550, false).map(|f| f.weight), Some(700));
+        assert_eq!(registry.resolve("Test", 300, false).map(|f| f.weight), Some(400));
+        assert_eq!(registry.resolve("Test", 700, true).map(|f| (f.weight, f.italic)), Some((400, true)));
+        assert!(registry.check("Test", 700, false).is_none());
+        assert_eq!(registry.check("Test", 900, false).and_then(|m| m.substitute).map(|f| f.weight), Some(700));
+        assert!(registry.check("Nope", 400, false).unwrap().substitute.is_none());
+    }
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::alignment::AlignmentReport;
+use super::types::{ClipExportOptions, ClipRenderOptions, SubtitleSettings, WordInfo};
+use super::captions::{caption_file_extension, render_caption_document, CaptionCue};
+
+// Bump when the manifest layout changes in a way readers need to know about
+pub const CLIP_MANIFEST_VERSION: u32 = 1;
+pub const CLIP_MANIFEST_FILENAME: &str = "manifest.json";
+
+// Manifest written into each clip folder describing how the clip was built.
+// It contains everything needed to audit or rebuild the clip without the database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipManifest {
+    pub manifest_version: u32,
+    pub clip_id: String,
+    pub project_id: String,
+    pub clip_name: String,
+    pub created_at: u64,
+    pub source: ManifestSource,
+    pub segments: Vec<ManifestSegment>,
+    pub intro: Option<ManifestBookend>,
+    pub outro: Option<ManifestBookend>,
+    pub subtitles: Option<ManifestSubtitles>,
+    // Transcript words inside the clip segments, on the source timeline (after alignment, before
+    // censoring), so captions can be regenerated from the manifest alone
+    #[serde(default)]
+    pub transcript_words: Vec<WordInfo>,
+    // Words of each additional caption track inside the clip segments
+    #[serde(default)]
+    pub caption_tracks: Vec<ManifestCaptionTrack>,
+    pub encoder: ManifestEncoder,
+    // Overlays and other final-pass render options (absent in older manifests)
+    #[serde(default)]
+    pub render: Option<ClipRenderOptions>,
+    // Total duration of the source segments (without intro/outro)
+    pub segments_duration: f64,
+    pub outputs: Vec<ManifestOutput>,
+    pub sidecars: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSource {
+    pub path: String,
+    pub file_size: Option<u64>,
+    pub modified_at: Option<u64>,
+    pub width: u32,
+    pub height: u32,
+    pub codec: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSegment {
+    pub id: Option<serde_json::Value>,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestBookend {
+    pub path: String,
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSubtitles {
+    pub settings: SubtitleSettings,
+    pub max_words: usize,
+    pub transcript_word_count: usize,
+    // Words moved by audio alignment (None when alignment was off)
+    #[serde(default)]
+    pub alignment: Option<AlignmentReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestCaptionTrack {
+    pub language: String,
+    pub words: Vec<WordInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEncoder {
+    pub codec: String,
+    pub preset: Option<String>,
+    pub quality_param: String,
+    pub quality_value: String,
+    pub quality: String,
+    pub frame_rate: u32,
+    pub output_format: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestOutput {
+    pub aspect_ratio: String,
+    pub file: ManifestFile,
+    pub duration: Option<f64>,
+    // Caption language burned into this output (None = the main transcript)
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+// A file in the clip folder, referenced relative to the folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub kind: String,
+    pub filename: String,
+    pub file_size: u64,
+    pub sha256: String,
+}
+
+impl ManifestSegment {
+    pub fn from_json(segment: &serde_json::Value) -> Self {
+        let start_time = segment["start_time"].as_f64().unwrap_or(0.0);
+        let end_time = segment["end_time"].as_f64().unwrap_or(0.0);
+        ManifestSegment {
+            id: segment.get("id").cloned(),
+            start_time,
+            end_time,
+            duration: end_time - start_time,
+        }
+    }
+}
+
+impl ManifestSource {
+    pub fn from_path(path: &str, video_info: &crate::ffmpeg_utils::VideoInfo) -> Self {
+        let metadata = std::fs::metadata(path).ok();
+        ManifestSource {
+            path: path.to_string(),
+            file_size: metadata.as_ref().map(|m| m.len()),
+            modified_at: metadata
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            width: video_info.width,
+            height: video_info.height,
+            codec: video_info.codec.clone(),
+        }
+    }
+}
+
+// Compute the SHA-256 of a file, streaming it in blocks (blocking - run off the async runtime)
+pub fn sha256_file(path: &std::path::Path) -> Result<String, String> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {} for hashing: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)
+            .map_err(|e| format!("Failed to read {} for hashing: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// Describe a file in the clip folder (size + checksum)
+pub fn describe_file(kind: &str, path: &std::path::Path) -> Result<ManifestFile, String> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path.display(), e))?;
+    Ok(ManifestFile {
+        kind: kind.to_string(),
+        filename: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        file_size: metadata.len(),
+        sha256: sha256_file(path)?,
+    })
+}
+
+// Write caption sidecars (.srt/.vtt) for the requested formats
+pub fn write_caption_sidecars(
+    clip_dir: &std::path::Path,
+    cues: &[CaptionCue],
+    formats: &[String],
+    language: Option<&str>
+) -> Result<Vec<std::path::PathBuf>, String> {
+    let mut written = Vec::new();
+
+    for format in formats {
+        let (Some(extension), Some(content)) = (caption_file_extension(format), render_caption_document(cues, format, language)) else {
+            println!("[Rust] Skipping unsupported caption sidecar format: {}", format);
+            continue;
+        };
+        // Caption tracks get the language in the name (captions.es.srt)
+        let filename = match language {
+            Some(language) => format!("captions.{}.{}", language, extension),
+            None => format!("captions.{}", extension),
+        };
+        let path = clip_dir.join(&filename);
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write caption sidecar {}: {}", filename, e))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+// Write description.txt with the description and hashtags (if any were provided)
+pub fn write_description_file(
+    clip_dir: &std::path::Path,
+    options: &ClipExportOptions
+) -> Result<Option<std::path::PathBuf>, String> {
+    let description = options.description.as_deref().map(str::trim).unwrap_or("");
+    let hashtags: Vec<String> = options.hashtags
+        .iter()
+        .map(|tag| tag.trim().trim_start_matches('#').replace(' ', ""))
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| format!("#{}", tag))
+        .collect();
+
+    if description.is_empty() && hashtags.is_empty() {
+        return Ok(None);
+    }
+
+    let mut content = description.to_string();
+    if !hashtags.is_empty() {
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&hashtags.join(" "));
+    }
+    content.push('\n');
+
+    let path = clip_dir.join("description.txt");
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write description file: {}", e))?;
+    Ok(Some(path))
+}
+
+// Write the manifest into the clip folder
+pub fn write_clip_manifest(
+    clip_dir: &std::path::Path,
+    manifest: &ClipManifest
+) -> Result<std::path::PathBuf, String> {
+    let path = clip_dir.join(CLIP_MANIFEST_FILENAME);
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize clip manifest: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write clip manifest: {}", e))?;
+    println!("[Rust] Wrote clip manifest: {}", path.display());
+    Ok(path)
+}
+
+// Read a manifest back (for re-import/auditing tools)
+pub fn read_clip_manifest_file(path: &std::path::Path) -> Result<ClipManifest, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read clip manifest: {}", e))?;
+    let manifest: ClipManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse clip manifest: {}", e))?;
+
+    if manifest.manifest_version > CLIP_MANIFEST_VERSION {
+        return Err(format!(
+            "Clip manifest version {} is newer than supported version {}",
+            manifest.manifest_version, CLIP_MANIFEST_VERSION
+        ));
+    }
+    Ok(manifest)
+}
@@ -7,9 +7,28 @@ mod video_info;
 mod subtitle;
 mod video_processor;
 mod orchestrator;
+pub(crate) mod scheduler;
+mod batch;
+mod captions;
+mod caption_chunker;
+mod manifest;
+mod artifact_cache;
+mod overlay;
+mod animation;
+mod text_metrics;
+mod script;
+mod censor;
+mod decoration;
+mod font_registry;
+mod preview;
+mod subtitle_import;
+mod preset;
+pub(crate) mod alignment;
+pub(crate) mod speech_enhancement;
 
 // Re-export public types
 pub use types::*;
+pub(crate) use video_processor::read_audio_samples;
 
 // Internal imports
 use once_cell::sync::Lazy;
@@ -17,6 +36,8 @@ use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 use tauri::Emitter;
 use orchestrator::build_clip_internal_simple;
+use video_info::IntroOutroCache;
+use batch::{run_clip_batch, BatchSharedSettings};
 
 // Active clip builds tracking
 static ACTIVE_CLIP_BUILDS: Lazy<Arc<Mutex<HashMap<String, bool>>>> = Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
@@ -42,7 +63,9 @@ pub async fn build_clip_from_segments(
     intro_path: Option<String>,
     intro_duration: Option<f64>,
     outro_path: Option<String>,
-    outro_duration: Option<f64>
+    outro_duration: Option<f64>,
+    export_options: Option<ClipExportOptions>,
+    render_options: Option<ClipRenderOptions>
 ) -> Result<(), String> {
 
     println!("[Rust] build_clip_from_segments called with:");
@@ -60,6 +83,7 @@ pub async fn build_clip_from_segments(
     println!("[Rust]   run_number: {:?}", run_number);
     println!("[Rust]   intro_path: {:?}", intro_path);
     println!("[Rust]   outro_path: {:?}", outro_path);
+    println!("[Rust]   overlays: {}", render_options.as_ref().map(|o| o.overlays.len()).unwrap_or(0));
 
     // Check if clip is already being built
     {
@@ -119,7 +143,10 @@ pub async fn build_clip_from_segments(
             intro_path_clone.as_deref(),
             intro_duration,
             outro_path_clone.as_deref(),
-            outro_duration
+            outro_duration,
+            export_options,
+            render_options,
+            Arc::new(Mutex::new(IntroOutroCache::default()))
         ).await {
             Ok(result) => {
                 println!("[Rust] Clip build completed successfully for: {}", clip_id_clone);
@@ -159,6 +186,99 @@ pub async fn build_clip_from_segments(
     Ok(())
 }
 
+// Build several clips of the same video as one batch with shared settings.
+// Encoder probing and intro/outro preparation are shared across clips, FFmpeg work is
+// scheduled against the global CPU budget, and per-clip events are emitted as usual.
+// Returns the batch ID; "clip-batch-complete" carries the summary when everything is done.
+#[tauri::command]
+pub async fn build_clips_batch(
+    app: tauri::AppHandle,
+    project_id: String,
+    video_path: String,
+    clips: Vec<BatchClipItem>,
+    subtitle_settings: Option<SubtitleSettings>,
+    transcript_words: Option<Vec<WordInfo>>,
+    transcript_segments: Option<Vec<WhisperSegment>>,
+    max_words: Option<usize>,
+    aspect_ratios: Vec<String>,
+    quality: String,
+    frame_rate: u32,
+    output_format: String,
+    run_number: Option<u32>,
+    intro_path: Option<String>,
+    intro_duration: Option<f64>,
+    outro_path: Option<String>,
+    outro_duration: Option<f64>,
+    export_options: Option<ClipExportOptions>,
+    render_options: Option<ClipRenderOptions>
+) -> Result<String, String> {
+    let batch_id = uuid::Uuid::new_v4().to_string();
+
+    println!("[Rust] build_clips_batch called with:");
+    println!("[Rust]   batch_id: {}", batch_id);
+    println!("[Rust]   project_id: {}", project_id);
+    println!("[Rust]   clips count: {}", clips.len());
+    println!("[Rust]   aspect_ratios: {:?}", aspect_ratios);
+
+    if clips.is_empty() {
+        return Err("No clips provided for batch build".to_string());
+    }
+
+    // Register every clip up front so the batch is all-or-nothing against running builds
+    {
+        let mut active_builds = ACTIVE_CLIP_BUILDS.lock().unwrap();
+        if let Some(busy) = clips.iter().find(|clip| active_builds.contains_key(&clip.clip_id)) {
+            return Err(format!("Clip {} is already being built", busy.clip_id));
+        }
+        for clip in &clips {
+            active_builds.insert(clip.clip_id.clone(), true);
+        }
+    }
+
+    let shared = BatchSharedSettings {
+        project_id,
+        video_path,
+        subtitle_settings,
+        transcript_words,
+        transcript_segments,
+        max_words,
+        aspect_ratios,
+        quality,
+        frame_rate,
+        output_format,
+        run_number,
+        intro_path,
+        intro_duration,
+        outro_path,
+        outro_duration,
+        export_options,
+        render_options,
+    };
+
+    let _ = app.emit("clip-batch-progress", ClipBatchProgress {
+        batch_id: batch_id.clone(),
+        project_id: shared.project_id.clone(),
+        total: clips.len(),
+        completed: 0,
+        failed: 0,
+        cancelled: 0,
+        progress: 0.0,
+        current_clip_id: None,
+        message: format!("Starting batch of {} clips...", clips.len()),
+    });
+
+    let app_clone = app.clone();
+    let batch_id_clone = batch_id.clone();
+    let _ = tokio::spawn(async move {
+        let summary = run_clip_batch(app_clone.clone(), batch_id_clone, shared, clips).await;
+        if let Err(e) = app_clone.emit("clip-batch-complete", &summary) {
+            println!("[Rust] Failed to emit clip-batch-complete event: {}", e);
+        }
+    });
+
+    Ok(batch_id)
+}
+
 // Cancel clip build
 #[tauri::command]
 pub async fn cancel_clip_build(clip_id: String) -> Result<bool, String> {
@@ -177,3 +297,155 @@ pub async fn is_clip_build_active(clip_id: String) -> Result<bool, String> {
     let active_builds = ACTIVE_CLIP_BUILDS.lock().unwrap();
     Ok(active_builds.contains_key(&clip_id))
 }
+
+// Read the manifest.json written next to a built clip
+#[tauri::command]
+pub async fn read_clip_manifest(manifest_path: String) -> Result<manifest::ClipManifest, String> {
+    manifest::read_clip_manifest_file(std::path::Path::new(&manifest_path))
+}
+
+// Export a clip's captions (same timeline and paging as the burned captions) as SRT, WebVTT or TTML
+#[tauri::command]
+pub async fn export_clip_captions(
+    transcript_words: Vec<WordInfo>,
+    segments: Vec<serde_json::Value>,
+    format: String,
+    output_path: String,
+    max_words: Option<usize>,
+    intro_duration: Option<f64>,
+    chunking: Option<CaptionChunking>,
+    language: Option<String>,
+) -> Result<String, String> {
+    println!("[Rust] Exporting {} captions to {}", format, output_path);
+    let cues = captions::clip_caption_cues(
+        &transcript_words,
+        &segments,
+        &chunking.unwrap_or_default(),
+        max_words.unwrap_or(4),
+        intro_duration.unwrap_or(0.0)
+    );
+    captions::export_caption_file(&cues, &format, language.as_deref(), std::path::Path::new(&output_path))?;
+    Ok(output_path)
+}
+
+// Render one frame with the export captions burned in (true appearance of a subtitle preset)
+#[tauri::command]
+pub async fn render_subtitle_preview(app: tauri::AppHandle, request: SubtitlePreviewRequest) -> Result<String, String> {
+    let path = preview::render_subtitle_preview(&app, &request).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// Named speech enhancement presets (filter settings for the clip builder and transcription audio)
+#[tauri::command]
+pub async fn list_speech_enhancement_presets() -> Result<Vec<speech_enhancement::SpeechEnhancementPreset>, String> {
+    Ok(speech_enhancement::speech_enhancement_presets())
+}
+
+// Render a short sample of the audio with and without the enhancement settings
+#[tauri::command]
+pub async fn render_speech_enhancement_preview(
+    app: tauri::AppHandle,
+    request: SpeechEnhancementPreviewRequest
+) -> Result<SpeechEnhancementPreview, String> {
+    preview::render_speech_enhancement_preview(&app, &request).await
+}
+
+// List the fonts available to subtitles (bundled and user-installed)
+#[tauri::command]
+pub async fn list_subtitle_fonts(app: tauri::AppHandle) -> Result<Vec<font_registry::FontFace>, String> {
+    let fonts_dir = font_manager::get_fonts_dir(&app).ok();
+    Ok(font_registry::font_registry(fonts_dir.as_deref()).faces().to_vec())
+}
+
+// Install a font file (.ttf/.otf) into the user fonts directory
+#[tauri::command]
+pub async fn install_subtitle_font(source_path: String) -> Result<font_registry::FontFace, String> {
+    font_registry::install_user_font(std::path::Path::new(&source_path))
+}
+
+// Fonts the subtitle settings and text overlays ask for that would be substituted or are missing,
+// so the frontend can warn before a build starts
+#[tauri::command]
+pub async fn check_subtitle_fonts(
+    app: tauri::AppHandle,
+    subtitle_settings: Option<SubtitleSettings>,
+    text_overlays: Option<Vec<TextOverlayItem>>,
+) -> Result<Vec<font_registry::MissingFont>, String> {
+    let fonts_dir = font_manager::get_fonts_dir(&app).ok();
+    let registry = font_registry::font_registry(fonts_dir.as_deref());
+
+    let styles = subtitle_settings
+        .iter()
+        .filter(|settings| settings.enabled)
+        .chain(text_overlays.iter().flatten().map(|item| &item.style));
+    let mut missing: Vec<font_registry::MissingFont> = Vec::new();
+    for style in styles {
+        let already_reported = missing.iter().any(|m| {
+            m.family == style.font_family && m.weight == style.font_weight && m.italic == style.italic
+        });
+        if already_reported {
+            continue;
+        }
+        if let Some(report) = registry.check(&style.font_family, style.font_weight, style.italic) {
+            missing.push(report);
+        }
+    }
+
+    // Emoji from decoration rules and in overlay text switch to the bundled emoji font
+    let uses_emoji = subtitle_settings
+        .iter()
+        .filter(|settings| settings.enabled)
+        .any(|settings| settings.decorations.iter().any(|rule| rule.emoji.is_some()))
+        || text_overlays
+            .iter()
+            .flatten()
+            .any(|item| item.text.chars().any(subtitle::is_emoji));
+    if uses_emoji {
+        if let Some(report) = registry.check(subtitle::EMOJI_FONT_FAMILY, 400, false) {
+            missing.push(report);
+        }
+    }
+    Ok(missing)
+}
+
+// Import an existing caption file (SRT, WebVTT, ASS/SSA) as transcript segments and words,
+// which then render through the same styled caption pipeline as a Whisper transcript
+#[tauri::command]
+pub async fn import_subtitle_file(path: String) -> Result<subtitle_import::ImportedSubtitles, String> {
+    subtitle_import::import_subtitle_file(std::path::Path::new(&path))
+}
+
+// Write subtitle settings to a versioned preset file for sharing
+#[tauri::command]
+pub async fn export_subtitle_preset(
+    path: String,
+    name: String,
+    description: Option<String>,
+    subtitle_settings: SubtitleSettings,
+) -> Result<(), String> {
+    preset::write_subtitle_preset(std::path::Path::new(&path), &name, description.as_deref(), &subtitle_settings)
+}
+
+// Read a preset file (older versions are migrated) and validate it
+#[tauri::command]
+pub async fn import_subtitle_preset(path: String) -> Result<preset::SubtitlePresetFile, String> {
+    preset::read_subtitle_preset(std::path::Path::new(&path))
+}
+
+// Get artifact cache size and limits
+#[tauri::command]
+pub async fn get_artifact_cache_stats() -> Result<artifact_cache::ArtifactCacheStats, String> {
+    Ok(artifact_cache::artifact_cache()?.stats())
+}
+
+// Set artifact cache limits (evicts least-recently-used entries if over the new limits)
+#[tauri::command]
+pub async fn set_artifact_cache_limits(max_bytes: u64, max_entries: usize) -> Result<(), String> {
+    artifact_cache::set_cache_limits(max_bytes, max_entries)
+}
+
+// Remove all cached build artifacts
+#[tauri::command]
+pub async fn clear_artifact_cache() -> Result<usize, String> {
+    artifact_cache::artifact_cache()?.clear()
+}
@@ -2,12 +2,32 @@ use std::sync::{Arc, Mutex};
 use futures::future::join_all;
 use tauri::Emitter;
 
-use super::types::{SubtitleSettings, WordInfo, WhisperSegment, ClipBuildProgress, ClipBuildResult};
+use super::types::{
+    SubtitleSettings, WordInfo, WhisperSegment, ClipBuildProgress, ClipBuildResult, ClipExportOptions, ClipRenderOptions,
+    CaptionTrack, CaptionTrackOutput,
+};
 use super::video_info::{get_video_info, parse_aspect_ratio, IntroOutroCache};
-use super::subtitle::generate_ass_file;
-use super::video_processor::{build_single_segment_clip_with_settings, build_multi_segment_clip_with_settings};
+use super::subtitle::{generate_ass_file, generate_text_overlay_ass_file};
+use super::video_processor::{
+    build_single_segment_clip_with_settings, build_multi_segment_clip_with_settings, mux_soft_subtitles, read_audio_samples,
+};
+use super::alignment::{align_words, AlignmentReport, SpeechActivity, ALIGNMENT_MARGIN_SECS, ALIGNMENT_SAMPLE_RATE};
+use super::captions::{clip_caption_cues, export_caption_file, label_speakers, track_words, words_in_segments};
+use super::censor::{censor_caption_words, censored_intervals};
 use super::thumbnail::generate_clip_thumbnail_simple;
 use super::font_manager::get_fonts_dir;
+use super::encoder::detect_hardware_encoder;
+use super::overlay::validate_overlays;
+use super::manifest::{
+    ClipManifest, ManifestSource, ManifestSegment, ManifestBookend, ManifestSubtitles, ManifestCaptionTrack, ManifestEncoder,
+    ManifestOutput, ManifestFile, CLIP_MANIFEST_VERSION, describe_file, write_caption_sidecars,
+    write_description_file, write_clip_manifest,
+};
+
+// Language code safe for use in file names ("pt-BR" stays, anything else is dropped)
+fn sanitize_language_code(language: &str) -> String {
+    language.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect()
+}
 
 // Helper function to sanitize a clip name for use as a folder name
 fn sanitize_clip_name(name: &str) -> String {
@@ -37,7 +57,7 @@ fn sanitize_clip_name(name: &str) -> String {
 
 // Helper function to get or create the run folder for a project using database-tracked run numbers
 // If run_number is None (manually generated clips), uses a special "manual" folder
-fn get_or_create_run_folder(
+pub(super) fn get_or_create_run_folder(
     project_clips_dir: &std::path::Path,
     run_number: Option<u32>
 ) -> Result<std::path::PathBuf, String> {
@@ -71,6 +91,39 @@ fn get_or_create_clip_folder(
     Ok(clip_folder)
 }
 
+// Align transcript words against the audio of each clip segment. Segments whose audio can't be
+// decoded or has no clear speech boundaries keep their word times.
+async fn align_clip_words(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    segments: &[serde_json::Value],
+    words: &mut [WordInfo]
+) -> AlignmentReport {
+    let mut report = AlignmentReport::default();
+    for segment in segments {
+        let (Some(start), Some(end)) = (segment["start_time"].as_f64(), segment["end_time"].as_f64()) else {
+            continue;
+        };
+        let audio_start = (start - ALIGNMENT_MARGIN_SECS).max(0.0);
+        let duration = end + ALIGNMENT_MARGIN_SECS - audio_start;
+        match read_audio_samples(app, video_path, audio_start, duration, ALIGNMENT_SAMPLE_RATE).await {
+            Ok(samples) => match SpeechActivity::from_samples(&samples, ALIGNMENT_SAMPLE_RATE, audio_start) {
+                Some(activity) => align_words(words, &activity, &mut report),
+                None => println!("[Rust] No clear speech boundaries in {:.2}-{:.2}s, keeping word times", start, end),
+            },
+            Err(e) => println!("[Rust] Skipping word alignment for {:.2}-{:.2}s: {}", start, end, e),
+        }
+    }
+    for shift in &report.moved {
+        println!(
+            "[Rust] Aligned '{}': {:.2}-{:.2}s -> {:.2}-{:.2}s",
+            shift.word, shift.original_start, shift.original_end, shift.start, shift.end
+        );
+    }
+    println!("[Rust] Word alignment moved {} of {} words", report.moved.len(), report.aligned_words);
+    report
+}
+
 // Simplified internal clip building implementation (without progress callbacks)
 pub async fn build_clip_internal_simple(
     app: &tauri::AppHandle,
@@ -81,7 +134,7 @@ pub async fn build_clip_internal_simple(
     segments: &[serde_json::Value],
     subtitle_settings: Option<SubtitleSettings>,
     transcript_words: Option<Vec<WordInfo>>,
-    _transcript_segments: Option<Vec<WhisperSegment>>,
+    transcript_segments: Option<Vec<WhisperSegment>>,
     max_words: Option<usize>,
     aspect_ratios: &[String],
     quality: &str,
@@ -91,7 +144,10 @@ pub async fn build_clip_internal_simple(
     intro_path: Option<&str>,
     intro_duration: Option<f64>,
     outro_path: Option<&str>,
-    _outro_duration: Option<f64>
+    outro_duration: Option<f64>,
+    export_options: Option<ClipExportOptions>,
+    render_options: Option<ClipRenderOptions>,
+    intro_outro_cache: Arc<Mutex<IntroOutroCache>>
 ) -> Result<ClipBuildResult, String> {
 
     // Emit progress
@@ -104,6 +160,11 @@ pub async fn build_clip_internal_simple(
         error: None,
     });
 
+    // Fail fast on missing overlay files before any encoding starts
+    if let Some(options) = &render_options {
+        validate_overlays(&options.overlays)?;
+    }
+
     // Get storage paths
     let paths = crate::storage::init_storage_dirs()
         .map_err(|e| format!("Failed to get storage paths: {}", e))?;
@@ -129,15 +190,76 @@ pub async fn build_clip_internal_simple(
     let mut first_thumbnail_path: Option<std::path::PathBuf> = None;
     let mut total_file_size: u64 = 0;
     let mut clip_duration: Option<f64> = None;
-    
-    // Create intro/outro cache for this build session (thread-safe for parallel builds)
-    let intro_outro_cache = Arc::new(Mutex::new(IntroOutroCache::new()));
 
-    // Build clips for all aspect ratios IN PARALLEL for maximum speed
+    let export_options = export_options.unwrap_or_default();
+
+    // Words without a speaker label of their own take their segment's (for per-speaker caption styles)
+    let transcript_words = match (transcript_words, &transcript_segments) {
+        (Some(words), Some(segments)) => Some(label_speakers(&words, segments)),
+        (words, _) => words,
+    };
+
+    // Snap word times to the clip audio before captions and censor ranges are derived from them
+    let mut alignment_report: Option<AlignmentReport> = None;
+    let transcript_words = match transcript_words {
+        Some(mut words) if render_options.as_ref().map(|o| o.align_words).unwrap_or(false) => {
+            alignment_report = Some(align_clip_words(app, video_path, segments, &mut words).await);
+            Some(words)
+        },
+        words => words,
+    };
+
+    // Uncensored clip words for the manifest
+    let manifest_words = transcript_words.as_deref().map(|words| words_in_segments(words, segments)).unwrap_or_default();
+    let manifest_tracks: Vec<ManifestCaptionTrack> = export_options.caption_tracks
+        .iter()
+        .map(|track| ManifestCaptionTrack {
+            language: track.language.clone(),
+            words: words_in_segments(&track_words(track), segments),
+        })
+        .collect();
+
+    // Profanity censoring: audio ranges come from the original words, every caption output
+    // (burned, soft, sidecars) uses the masked words
+    let censor = render_options.as_ref().and_then(|o| o.censor.clone()).filter(|c| !c.words.is_empty());
+    let censor_intervals = match (&censor, &transcript_words) {
+        (Some(censor), Some(words)) => censored_intervals(words, censor),
+        _ => Vec::new(),
+    };
+    let transcript_words = match (&censor, transcript_words) {
+        (Some(censor), Some(words)) => Some(censor_caption_words(&words, censor)),
+        (_, words) => words,
+    };
+    let caption_track_words = |track: &CaptionTrack| match &censor {
+        Some(censor) => censor_caption_words(&track_words(track), censor),
+        None => track_words(track),
+    };
+    if !censor_intervals.is_empty() {
+        println!("[Rust] Censoring {} audio ranges", censor_intervals.len());
+    }
+
+    // Caption variants per aspect ratio: the main transcript, plus one output per language
+    // when extra caption tracks are burned in
+    let mut caption_variants: Vec<Option<CaptionTrack>> = vec![None];
+    if export_options.caption_track_output == CaptionTrackOutput::Burned && !export_options.caption_tracks.is_empty() {
+        if subtitle_settings.as_ref().map(|s| s.enabled).unwrap_or(false) {
+            caption_variants.extend(export_options.caption_tracks.iter().cloned().map(Some));
+        } else {
+            println!("[Rust] Subtitles are disabled, not burning {} caption tracks", export_options.caption_tracks.len());
+        }
+    }
+    let build_jobs: Vec<(usize, String, Option<CaptionTrack>)> = aspect_ratios
+        .iter()
+        .enumerate()
+        .flat_map(|(ratio_idx, ratio)| caption_variants.iter().map(move |variant| (ratio_idx, ratio.clone(), variant.clone())))
+        .collect();
+
+    // Build clips for all aspect ratios (and caption languages) IN PARALLEL for maximum speed
     let total_ratios = aspect_ratios.len();
-    println!("[Rust] Building {} aspect ratios in parallel...", total_ratios);
+    let total_jobs = build_jobs.len();
+    println!("[Rust] Building {} aspect ratios ({} outputs) in parallel...", total_ratios, total_jobs);
     
-    let build_tasks: Vec<_> = aspect_ratios.iter().enumerate().map(|(ratio_idx, aspect_ratio_str)| {
+    let build_tasks: Vec<_> = build_jobs.into_iter().enumerate().map(|(job_idx, (ratio_idx, aspect_ratio_str, caption_track))| {
         let app = app.clone();
         let video_path = video_path.to_string();
         let clip_id = clip_id.to_string();
@@ -145,38 +267,51 @@ pub async fn build_clip_internal_simple(
         let clip_base_dir = clip_base_dir.clone();
         let segments = segments.to_vec();
         let subtitle_settings = subtitle_settings.clone();
-        let transcript_words = transcript_words.clone();
         let quality = quality.to_string();
         let output_format = output_format.to_string();
         let intro_path = intro_path.map(|s| s.to_string());
         let outro_path = outro_path.map(|s| s.to_string());
         let video_info = video_info.clone();
         let intro_outro_cache = intro_outro_cache.clone();
-        let aspect_ratio_str = aspect_ratio_str.clone();
+        let render_options = render_options.clone();
+        let censor_intervals = censor_intervals.clone();
+        let language = caption_track.as_ref().map(|track| track.language.clone());
+        // Burned caption tracks replace the transcript words for their output
+        let caption_words = match &caption_track {
+            Some(track) => Some(caption_track_words(track)),
+            None => transcript_words.clone(),
+        };
         
         async move {
-            println!("[Rust] Building clip for aspect ratio: {}", aspect_ratio_str);
+            println!("[Rust] Building clip for aspect ratio: {} (captions: {})", aspect_ratio_str, language.as_deref().unwrap_or("transcript"));
             
-            let progress_start = 10.0 + (ratio_idx as f64 / total_ratios as f64) * 75.0;
+            let progress_start = 10.0 + (job_idx as f64 / total_jobs as f64) * 75.0;
             let _ = app.emit("clip-build-progress", ClipBuildProgress {
                 clip_id: clip_id.clone(),
                 project_id: project_id.clone(),
                 progress: progress_start,
                 stage: "building".to_string(),
-                message: format!("Building {} ({}/{})", aspect_ratio_str, ratio_idx + 1, total_ratios),
+                message: match &language {
+                    Some(language) => format!("Building {} [{}] ({}/{})", aspect_ratio_str, language, job_idx + 1, total_jobs),
+                    None => format!("Building {} ({}/{})", aspect_ratio_str, job_idx + 1, total_jobs),
+                },
                 error: None,
             });
 
             // Parse aspect ratio string (e.g., "16:9")
             let aspect_ratio = parse_aspect_ratio(&aspect_ratio_str)?;
             
-            // Create filename with aspect ratio (replace : with -) and selected format (mp4/mov)
-            let ratio_suffix = aspect_ratio_str.replace(":", "-");
+            // Create filename with aspect ratio (replace : with -), caption language and selected format (mp4/mov)
+            let ratio_suffix = format!(
+                "{}{}",
+                aspect_ratio_str.replace(":", "-"),
+                language.as_deref().map(|l| format!("_{}", sanitize_language_code(l))).unwrap_or_default()
+            );
             let output_filename = format!("clip_{}.{}", ratio_suffix, output_format);
             let output_path = clip_base_dir.join(&output_filename);
 
             // Generate subtitle file if needed for this aspect ratio
-            let subtitle_file = if let (Some(settings), Some(words)) = (&subtitle_settings, &transcript_words) {
+            let subtitle_file = if let (Some(settings), Some(words)) = (&subtitle_settings, &caption_words) {
                 if settings.enabled {
                     // Get fonts directory
                     let fonts_dir = get_fonts_dir(&app).ok();
@@ -205,6 +340,27 @@ pub async fn build_clip_internal_simple(
                 None
             };
 
+            // Generate the text overlay track (headlines, CTAs) as a second subtitle file
+            let text_overlay_file = match render_options.as_ref().filter(|o| !o.text_overlays.is_empty()) {
+                Some(options) => {
+                    let fonts_dir = get_fonts_dir(&app).ok();
+                    let overlay_path = clip_base_dir.join(format!("text_overlays_{}.ass", ratio_suffix));
+                    generate_text_overlay_ass_file(
+                        &options.text_overlays,
+                        &overlay_path,
+                        Some(&aspect_ratio),
+                        video_info.width,
+                        video_info.height,
+                        fonts_dir.as_deref()
+                    ).map_err(|e| format!("Failed to generate text overlay file: {}", e))?;
+                    Some(overlay_path)
+                },
+                None => None,
+            };
+
+            // Captions first, then text overlays so titles draw on top
+            let subtitle_files: Vec<std::path::PathBuf> = subtitle_file.iter().chain(text_overlay_file.iter()).cloned().collect();
+
             // Build clip based on segments with aspect ratio cropping
             // Note: We pass the Arc<Mutex<>> cache, and lock/unlock inside the build functions
             if segments.len() == 1 {
@@ -214,13 +370,15 @@ pub async fn build_clip_internal_simple(
                     &video_path,
                     &output_path,
                     &segments[0],
-                    subtitle_file.as_deref(),
+                    &subtitle_files,
                     &aspect_ratio,
                     &quality,
                     frame_rate,
                     &output_format,
                     intro_path.as_deref(),
                     outro_path.as_deref(),
+                    render_options.as_ref(),
+                    &censor_intervals,
                     intro_outro_cache.clone()
                 ).await?;
             } else {
@@ -230,24 +388,27 @@ pub async fn build_clip_internal_simple(
                     &video_path,
                     &output_path,
                     &segments,
-                    subtitle_file.as_deref(),
+                    &subtitle_files,
                     &aspect_ratio,
                     &quality,
                     frame_rate,
                     &output_format,
                     intro_path.as_deref(),
                     outro_path.as_deref(),
+                    render_options.as_ref(),
+                    &censor_intervals,
                     intro_outro_cache.clone()
                 ).await?;
             }
 
-            // Clean up subtitle file
-            if let Some(sub_path) = subtitle_file {
+            // Clean up subtitle files
+            for sub_path in subtitle_files {
                 let _ = std::fs::remove_file(sub_path);
             }
 
-            // Generate thumbnail for the first aspect ratio
-            let thumbnail = if ratio_idx == 0 {
+            // Generate thumbnail for the first aspect ratio (main captions)
+            let is_primary_output = ratio_idx == 0 && language.is_none();
+            let thumbnail = if is_primary_output {
                 println!("[Rust] Generating thumbnail for first aspect ratio...");
                 generate_clip_thumbnail_simple(&app, &output_path, &clip_id).await?
             } else {
@@ -259,12 +420,8 @@ pub async fn build_clip_internal_simple(
                 .map_err(|e| format!("Failed to get output file metadata: {}", e))?;
             let file_size = metadata.len();
 
-            // Get clip duration (from first clip)
-            let duration = if ratio_idx == 0 {
-                crate::ffmpeg_utils::get_video_duration_sync(&app, output_path.to_str().ok_or("Invalid output path")?).await.ok()
-            } else {
-                None
-            };
+            // Get output duration (recorded per aspect ratio in the manifest)
+            let duration = crate::ffmpeg_utils::get_video_duration_sync(&app, output_path.to_str().ok_or("Invalid output path")?).await.ok();
 
             // Return build result
             Ok::<_, String>((
@@ -272,7 +429,9 @@ pub async fn build_clip_internal_simple(
                 file_size,
                 duration,
                 thumbnail,
-                ratio_idx
+                is_primary_output,
+                aspect_ratio_str,
+                language
             ))
         }
     }).collect();
@@ -281,13 +440,15 @@ pub async fn build_clip_internal_simple(
     let build_results = join_all(build_tasks).await;
     
     // Process results
+    let mut built_outputs = Vec::new();
     for result in build_results {
         match result {
-            Ok((output_path_str, file_size, duration, thumbnail, ratio_idx)) => {
+            Ok((output_path_str, file_size, duration, thumbnail, is_primary_output, aspect_ratio_str, language)) => {
                 all_output_paths.push(output_path_str.clone());
+                built_outputs.push((aspect_ratio_str, std::path::PathBuf::from(&output_path_str), duration, language));
                 total_file_size += file_size;
                 
-                if ratio_idx == 0 {
+                if is_primary_output {
                     first_output_path = Some(output_path_str);
                     first_thumbnail_path = thumbnail;
                     clip_duration = duration;
@@ -299,6 +460,146 @@ pub async fn build_clip_internal_simple(
     
     println!("[Rust] All {} aspect ratios built successfully in parallel!", total_ratios);
 
+    // Soft subtitle tracks, sidecars and the clip manifest (failures here are logged and don't fail
+    // the build - the clip is already encoded)
+    let encoder = detect_hardware_encoder(app, quality).await;
+    let mut sidecars: Vec<(&str, std::path::PathBuf)> = Vec::new();
+    let caption_chunking = subtitle_settings.as_ref().map(|s| s.chunking.clone()).unwrap_or_default();
+
+    // Caption word lists by language (None = the main transcript)
+    let mut caption_sources: Vec<(Option<String>, Vec<WordInfo>)> = Vec::new();
+    if let Some(words) = &transcript_words {
+        caption_sources.push((None, words.clone()));
+    }
+    for track in &export_options.caption_tracks {
+        caption_sources.push((Some(track.language.clone()), caption_track_words(track)));
+    }
+
+    // Soft subtitle tracks (independent of burn-in, so captions can be selectable instead of burned):
+    // the transcript if requested, and every caption track unless they were burned into their own outputs
+    let soft_sources: Vec<&(Option<String>, Vec<WordInfo>)> = caption_sources
+        .iter()
+        .filter(|(language, _)| match language {
+            None => export_options.soft_subtitles,
+            Some(_) => export_options.caption_track_output == CaptionTrackOutput::Soft,
+        })
+        .collect();
+    if !soft_sources.is_empty() {
+        let mut soft_tracks: Vec<(std::path::PathBuf, Option<String>)> = Vec::new();
+        for (language, words) in soft_sources {
+            let path = clip_base_dir.join(match language {
+                Some(language) => format!("soft_captions_{}.srt", sanitize_language_code(language)),
+                None => "soft_captions.srt".to_string(),
+            });
+            let cues = clip_caption_cues(words, segments, &caption_chunking, max_words.unwrap_or(4), intro_duration.unwrap_or(0.0));
+            match export_caption_file(&cues, "srt", language.as_deref(), &path) {
+                Ok(()) => soft_tracks.push((path, language.clone())),
+                Err(e) => println!("[Rust] Failed to write soft subtitle track: {}", e),
+            }
+        }
+
+        if !soft_tracks.is_empty() {
+            // A failed mux leaves that output as it was encoded
+            let mut muxed_outputs = 0;
+            for (_, output_path, _, _) in &built_outputs {
+                match mux_soft_subtitles(app, output_path, &soft_tracks).await {
+                    Ok(()) => muxed_outputs += 1,
+                    Err(e) => println!("[Rust] Failed to add soft subtitles to {}: {}", output_path.display(), e),
+                }
+            }
+            total_file_size = built_outputs
+                .iter()
+                .filter_map(|(_, path, _, _)| std::fs::metadata(path).ok())
+                .map(|m| m.len())
+                .sum();
+            println!("[Rust] Added {} soft subtitle tracks to {} of {} outputs", soft_tracks.len(), muxed_outputs, built_outputs.len());
+        }
+        for (path, _) in &soft_tracks {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    if !export_options.caption_formats.is_empty() {
+        for (language, words) in &caption_sources {
+            let cues = clip_caption_cues(words, segments, &caption_chunking, max_words.unwrap_or(4), intro_duration.unwrap_or(0.0));
+            match write_caption_sidecars(&clip_base_dir, &cues, &export_options.caption_formats, language.as_deref()) {
+                Ok(paths) => sidecars.extend(paths.into_iter().map(|p| ("captions", p))),
+                Err(e) => println!("[Rust] Failed to write caption sidecars: {}", e),
+            }
+        }
+    }
+
+    match write_description_file(&clip_base_dir, &export_options) {
+        Ok(Some(path)) => sidecars.push(("description", path)),
+        Ok(None) => {},
+        Err(e) => println!("[Rust] Failed to write description file: {}", e),
+    }
+
+    let segments_for_manifest: Vec<ManifestSegment> = segments.iter().map(ManifestSegment::from_json).collect();
+    let segments_duration = segments_for_manifest.iter().map(|s| s.duration).sum();
+
+    // Hash outputs and sidecars off the async runtime - clips can be hundreds of MB
+    let files_to_describe = built_outputs.clone();
+    let sidecars_to_describe = sidecars.iter().map(|(kind, path)| (kind.to_string(), path.clone())).collect::<Vec<_>>();
+    let described = tokio::task::spawn_blocking(move || {
+        let outputs = files_to_describe
+            .into_iter()
+            .map(|(aspect_ratio, path, duration, language)| {
+                describe_file("video", &path).map(|file| ManifestOutput { aspect_ratio, file, duration, language })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let sidecar_files = sidecars_to_describe
+            .into_iter()
+            .map(|(kind, path)| describe_file(&kind, &path))
+            .collect::<Result<Vec<ManifestFile>, String>>()?;
+        Ok::<_, String>((outputs, sidecar_files))
+    }).await.map_err(|e| format!("Failed to hash clip outputs: {}", e)).and_then(|r| r);
+
+    match described {
+        Ok((mut outputs, sidecar_files)) => {
+            outputs.sort_by(|a, b| (&a.aspect_ratio, &a.language).cmp(&(&b.aspect_ratio, &b.language)));
+            let manifest = ClipManifest {
+                manifest_version: CLIP_MANIFEST_VERSION,
+                clip_id: clip_id.to_string(),
+                project_id: project_id.to_string(),
+                clip_name: clip_name.to_string(),
+                created_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                source: ManifestSource::from_path(video_path, &video_info),
+                segments: segments_for_manifest,
+                intro: intro_path.map(|path| ManifestBookend { path: path.to_string(), duration: intro_duration }),
+                outro: outro_path.map(|path| ManifestBookend { path: path.to_string(), duration: outro_duration }),
+                subtitles: subtitle_settings.as_ref().filter(|s| s.enabled).map(|settings| ManifestSubtitles {
+                    settings: settings.clone(),
+                    max_words: max_words.unwrap_or(4),
+                    transcript_word_count: transcript_words.as_ref().map(|w| w.len()).unwrap_or(0),
+                    alignment: alignment_report.clone(),
+                }),
+                transcript_words: manifest_words,
+                caption_tracks: manifest_tracks,
+                encoder: ManifestEncoder {
+                    codec: encoder.codec.clone(),
+                    preset: encoder.preset.clone(),
+                    quality_param: encoder.quality_param.clone(),
+                    quality_value: encoder.quality_value.clone(),
+                    quality: quality.to_string(),
+                    frame_rate,
+                    output_format: output_format.to_string(),
+                },
+                render: render_options.clone(),
+                segments_duration,
+                outputs,
+                sidecars: sidecar_files,
+            };
+            if let Err(e) = write_clip_manifest(&clip_base_dir, &manifest) {
+                println!("[Rust] {}", e);
+            }
+        },
+        Err(e) => println!("[Rust] Skipping clip manifest: {}", e),
+    }
+
     // Emit completion progress
     println!("[Rust] Emitting completion progress event...");
     let result = ClipBuildResult {
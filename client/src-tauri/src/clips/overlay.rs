@@ -0,0 +1,189 @@
+use super::types::{OverlayPlacement, OverlaySpec};
+
+// Watermark/logo overlays. Overlays are extra FFmpeg inputs composited in the same
+// filter graph as the crop and the subtitles, so adding a logo never costs an extra encode.
+
+// Extensions decoded as a single still frame (looped for the whole clip)
+const STILL_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "bmp"];
+
+pub fn is_still_image(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .map(|e| STILL_IMAGE_EXTENSIONS.contains(&e.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// Whether the overlay input never ends on its own (still images and looped animations)
+fn is_endless(overlay: &OverlaySpec) -> bool {
+    is_still_image(&overlay.path) || overlay.loop_animation
+}
+
+// Check every overlay file exists before starting any FFmpeg work
+pub fn validate_overlays(overlays: &[OverlaySpec]) -> Result<(), String> {
+    for overlay in overlays {
+        if !std::path::Path::new(&overlay.path).exists() {
+            return Err(format!("Overlay file not found: {}", overlay.path));
+        }
+        if !(0.0..=1.0).contains(&overlay.opacity) {
+            return Err(format!("Overlay opacity must be between 0 and 1: {}", overlay.opacity));
+        }
+    }
+    Ok(())
+}
+
+// Input arguments for the overlays. They go after the main input, so overlay i is input i + 1.
+pub fn overlay_input_args(overlays: &[OverlaySpec]) -> Vec<String> {
+    let mut args = Vec::new();
+    for overlay in overlays {
+        if is_still_image(&overlay.path) {
+            args.extend_from_slice(&["-loop".to_string(), "1".to_string()]);
+        } else if overlay.loop_animation {
+            args.extend_from_slice(&["-stream_loop".to_string(), "-1".to_string()]);
+        }
+        args.extend_from_slice(&["-i".to_string(), overlay.path.clone()]);
+    }
+    args
+}
+
+// Placement for an aspect ratio ("9:16"), falling back to the default placement
+pub fn placement_for<'a>(overlay: &'a OverlaySpec, aspect_ratio: &str) -> &'a OverlayPlacement {
+    overlay.placements.get(aspect_ratio).unwrap_or(&overlay.placement)
+}
+
+// Overlay x/y expressions for an anchor, with margins as a percentage of the output size
+pub fn overlay_position(placement: &OverlayPlacement, output_width: u32, output_height: u32) -> (String, String) {
+    let margin_x = (placement.offset_x as f64 / 100.0 * output_width as f64).round() as i64;
+    let margin_y = (placement.offset_y as f64 / 100.0 * output_height as f64).round() as i64;
+    let anchor = placement.anchor.to_lowercase();
+
+    let x = if anchor.contains("left") {
+        margin_x.to_string()
+    } else if anchor.contains("right") {
+        format!("main_w-overlay_w-{}", margin_x)
+    } else {
+        "(main_w-overlay_w)/2".to_string()
+    };
+
+    let y = if anchor.contains("top") {
+        margin_y.to_string()
+    } else if anchor.contains("bottom") {
+        format!("main_h-overlay_h-{}", margin_y)
+    } else {
+        "(main_h-overlay_h)/2".to_string()
+    };
+
+    (x, y)
+}
+
+// Build the filter_complex for a render with overlays:
+// [0:v] -> pre_filter -> overlay 1..n -> post_filter -> [vout]
+// Overlays go under the post filter so burned captions stay on top of logos.
+pub fn build_overlay_filter_graph(
+    pre_filter: &str,
+    post_filter: Option<&str>,
+    overlays: &[OverlaySpec],
+    aspect_ratio: &str,
+    output_width: u32,
+    output_height: u32
+) -> String {
+    let pre = if pre_filter.is_empty() { "null" } else { pre_filter };
+    let mut chains = vec![format!("[0:v]{}[base0]", pre)];
+    let mut current = "base0".to_string();
+
+    for (i, overlay) in overlays.iter().enumerate() {
+        let placement = placement_for(overlay, aspect_ratio);
+        let width = ((placement.scale.max(0.01) as f64 * output_width as f64).round() as u32).max(2);
+
+        // Prepare the overlay stream: size relative to the output, alpha for opacity
+        let mut overlay_filters = vec![format!("scale={}:-1", width), "format=rgba".to_string()];
+        if overlay.opacity < 1.0 {
+            overlay_filters.push(format!("colorchannelmixer=aa={:.3}", overlay.opacity));
+        }
+        // Animations start playing when their window opens, not at the start of the clip
+        if let (Some(start), false) = (overlay.start, is_still_image(&overlay.path)) {
+            overlay_filters.push(format!("setpts=PTS-STARTPTS+{:.3}/TB", start));
+        }
+        chains.push(format!("[{}:v]{}[ovsrc{}]", i + 1, overlay_filters.join(","), i));
+
+        let (x, y) = overlay_position(placement, output_width, output_height);
+        let mut overlay_args = format!("overlay=x={}:y={}", x, y);
+        // Endless inputs must not keep the output running; finite ones just disappear at their end
+        if is_endless(overlay) {
+            overlay_args.push_str(":shortest=1");
+        } else {
+            overlay_args.push_str(":eof_action=pass");
+        }
+        if let Some(enable) = enable_expression(overlay.start, overlay.end) {
+            overlay_args.push_str(&format!(":enable='{}'", enable));
+        }
+
+        let next = format!("base{}", i + 1);
+        chains.push(format!("[{}][ovsrc{}]{}[{}]", current, i, overlay_args, next));
+        current = next;
+    }
+
+    chains.push(format!("[{}]{}[vout]", current, post_filter.unwrap_or("null")));
+    chains.join(";")
+}
+
+// Timeline expression for an overlay's visibility window
+fn enable_expression(start: Option<f64>, end: Option<f64>) -> Option<String> {
+    match (start, end) {
+        (Some(s), Some(e)) => Some(format!("between(t,{:.3},{:.3})", s, e)),
+        (Some(s), None) => Some(format!("gte(t,{:.3})", s)),
+        (None, Some(e)) => Some(format!("lte(t,{:.3})", e)),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logo(path: &str) -> OverlaySpec {
+        OverlaySpec {
+            path: path.to_string(),
+            placement: OverlayPlacement::default(),
+            placements: std::collections::HashMap::new(),
+            opacity: 1.0,
+            start: None,
+            end: None,
+            loop_animation: false,
+        }
+    }
+
+    #[test]
+    fn test_overlay_position_anchors() {
+        let mut placement = OverlayPlacement { anchor: "bottom-left".to_string(), offset_x: 5.0, offset_y: 10.0, scale: 0.2 };
+        assert_eq!(overlay_position(&placement, 1080, 1920), ("54".to_string(), "main_h-overlay_h-192".to_string()));
+
+        placement.anchor = "center".to_string();
+        assert_eq!(overlay_position(&placement, 1080, 1920).0, "(main_w-overlay_w)/2");
+    }
+
+    #[test]
+    fn test_filter_graph_uses_aspect_ratio_placement() {
+        let mut overlay = logo("/tmp/logo.png");
+        overlay.opacity = 0.5;
+        overlay.start = Some(1.0);
+        overlay.end = Some(4.0);
+        overlay.placements.insert("9:16".to_string(), OverlayPlacement { anchor: "top-left".to_string(), offset_x: 0.0, offset_y: 0.0, scale: 0.5 });
+
+        let graph = build_overlay_filter_graph("crop=1080:1920:0:0", Some("format=rgb24"), &[overlay], "9:16", 1080, 1920);
+        assert_eq!(
+            graph,
+            "[0:v]crop=1080:1920:0:0[base0];\
+             [1:v]scale=540:-1,format=rgba,colorchannelmixer=aa=0.500[ovsrc0];\
+             [base0][ovsrc0]overlay=x=0:y=0:shortest=1:enable='between(t,1.000,4.000)'[base1];\
+             [base1]format=rgb24[vout]"
+        );
+    }
+
+    #[test]
+    fn test_animated_overlay_inputs() {
+        let mut animated = logo("/tmp/sting.mov");
+        animated.loop_animation = true;
+        let args = overlay_input_args(&[logo("/tmp/logo.PNG"), animated]);
+        assert_eq!(args, vec!["-loop", "1", "-i", "/tmp/logo.PNG", "-stream_loop", "-1", "-i", "/tmp/sting.mov"]);
+    }
+}
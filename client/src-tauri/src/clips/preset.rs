@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::decoration::CaptionDecorator;
+use super::types::SubtitleSettings;
+
+// Subtitle preset files for sharing caption styles between machines. The settings are stored
+// as SubtitleSettings serializes them, so the Rust type is the schema; older files are migrated
+// up to the current version when read.
+//
+// Versions:
+//   1 - a flat custom_subtitle_presets row (snake_case columns, outline_* before border1/border2)
+//   2 - { version, name, description, settings: SubtitleSettings }
+
+pub const SUBTITLE_PRESET_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlePresetFile {
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub settings: SubtitleSettings,
+}
+
+// Columns of a version 1 row that are not settings
+const V1_METADATA_COLUMNS: [&str; 5] = ["id", "name", "description", "created_at", "updated_at"];
+
+fn snake_to_camel(key: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// Version 1 (database row) to version 2
+fn migrate_v1(row: Map<String, Value>) -> Map<String, Value> {
+    let mut settings = Map::new();
+    settings.insert("enabled".to_string(), Value::Bool(true));
+    for (key, value) in &row {
+        if V1_METADATA_COLUMNS.contains(&key.as_str()) {
+            continue;
+        }
+        let key = match key.as_str() {
+            // Renamed by migration 029
+            "outline_width" => "border1_width",
+            "outline_color" => "border1_color",
+            other => other,
+        };
+        let value = match (key, value) {
+            // SQLite boolean
+            ("background_enabled", Value::Number(n)) => Value::Bool(n.as_i64().unwrap_or(0) != 0),
+            // Legacy animation values all exported the pop (see migration 034)
+            ("animation_style", Value::String(style)) if ["none", "fade", "word-by-word"].contains(&style.as_str()) => Value::from("pop"),
+            _ => value.clone(),
+        };
+        settings.insert(snake_to_camel(key), value);
+    }
+    // Added by migration 029
+    settings.entry("border2Width").or_insert(Value::from(0.0));
+    settings.entry("border2Color").or_insert(Value::from("#000000"));
+
+    let mut file = Map::new();
+    file.insert("version".to_string(), Value::from(2));
+    file.insert("name".to_string(), row.get("name").cloned().unwrap_or(Value::from("Imported preset")));
+    file.insert("description".to_string(), row.get("description").cloned().unwrap_or(Value::Null));
+    file.insert("settings".to_string(), Value::Object(settings));
+    file
+}
+
+fn is_hex_color(color: &str) -> bool {
+    let hex = color.strip_prefix('#').unwrap_or("");
+    (hex.len() == 6 || hex.len() == 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Problems that would make the preset render wrongly (empty when the preset is usable)
+pub fn validate_subtitle_settings(settings: &SubtitleSettings) -> Vec<String> {
+    let mut problems = Vec::new();
+    if settings.font_family.trim().is_empty() {
+        problems.push("font family is empty".to_string());
+    }
+    if settings.font_size.is_nan() || settings.font_size <= 0.0 {
+        problems.push(format!("font size must be positive (got {})", settings.font_size));
+    }
+    if !(100..=900).contains(&settings.font_weight) {
+        problems.push(format!("font weight must be 100-900 (got {})", settings.font_weight));
+    }
+    if !(0.0..=100.0).contains(&settings.position_percentage) {
+        problems.push(format!("position percentage must be 0-100 (got {})", settings.position_percentage));
+    }
+    if !["top", "middle", "bottom"].contains(&settings.position.as_str()) {
+        problems.push(format!("unknown position '{}'", settings.position));
+    }
+    if !["left", "center", "right"].contains(&settings.text_align.as_str()) {
+        problems.push(format!("unknown text alignment '{}'", settings.text_align));
+    }
+    if settings.border1_width < 0.0 || settings.border2_width < 0.0 {
+        problems.push("border widths must not be negative".to_string());
+    }
+
+    let mut colors = vec![
+        ("text color", Some(&settings.text_color)),
+        ("background color", Some(&settings.background_color)),
+        ("border 1 color", Some(&settings.border1_color)),
+        ("border 2 color", Some(&settings.border2_color)),
+        ("shadow color", Some(&settings.shadow_color)),
+        ("active word color", settings.active_word_color.as_ref()),
+        ("active box color", settings.active_box_color.as_ref()),
+    ];
+    for style in &settings.speaker_styles {
+        colors.push(("speaker text color", style.text_color.as_ref()));
+        colors.push(("speaker active word color", style.active_word_color.as_ref()));
+    }
+    for rule in &settings.decorations {
+        colors.push(("decoration color", rule.color.as_ref()));
+    }
+    for (label, color) in colors {
+        if let Some(color) = color.filter(|c| !is_hex_color(c)) {
+            problems.push(format!("{} '{}' is not a #RRGGBB or #RRGGBBAA color", label, color));
+        }
+    }
+
+    if let Err(e) = CaptionDecorator::new(&settings.decorations) {
+        problems.push(e);
+    }
+    problems
+}
+
+// Parse a preset file of any known version into the current format
+pub fn parse_subtitle_preset(json: &str) -> Result<SubtitlePresetFile, String> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse subtitle preset: {}", e))?;
+    let Value::Object(mut object) = value else {
+        return Err("Failed to parse subtitle preset: expected a JSON object".to_string());
+    };
+
+    let version = object.get("version").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+    if version > SUBTITLE_PRESET_VERSION {
+        return Err(format!(
+            "Subtitle preset version {} is newer than this app supports ({})",
+            version, SUBTITLE_PRESET_VERSION
+        ));
+    }
+    if version == 1 {
+        object = migrate_v1(object);
+    }
+
+    let preset: SubtitlePresetFile = serde_json::from_value(Value::Object(object))
+        .map_err(|e| format!("Failed to read subtitle preset: {}", e))?;
+    let problems = validate_subtitle_settings(&preset.settings);
+    if !problems.is_empty() {
+        return Err(format!("Invalid subtitle preset '{}': {}", preset.name, problems.join("; ")));
+    }
+    if version < SUBTITLE_PRESET_VERSION {
+        println!("[Rust] Migrated subtitle preset '{}' from version {}", preset.name, version);
+    }
+    Ok(preset)
+}
+
+pub fn read_subtitle_preset(path: &std::path::Path) -> Result<SubtitlePresetFile, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read subtitle preset file: {}", e))?;
+    parse_subtitle_preset(&json)
+}
+
+pub fn write_subtitle_preset(
+    path: &std::path::Path,
+    name: &str,
+    description: Option<&str>,
+    settings: &SubtitleSettings
+) -> Result<(), String> {
+    let problems = validate_subtitle_settings(settings);
+    if !problems.is_empty() {
+        return Err(format!("Invalid subtitle preset '{}': {}", name, problems.join("; ")));
+    }
+    let preset = SubtitlePresetFile {
+        version: SUBTITLE_PRESET_VERSION,
+        name: name.to_string(),
+        description: description.map(|d| d.to_string()),
+        settings: settings.clone(),
+    };
+    let json = serde_json::to_string_pretty(&preset)
+        .map_err(|e| format!("Failed to serialize subtitle preset: {}", e))?;
+    std::fs::write(path, json)
+        .map_err(|e| format!("Failed to write subtitle preset file: {}", e))?;
+    println!("[Rust] Exported subtitle preset '{}' to {}", name, path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v1_row() -> Value {
+        serde_json::json!({
+            "id": "abc", "name": "Bold Yellow", "description": null,
+            "font_family": "Montserrat", "font_size": 48, "font_weight": 800,
+            "text_color": "#FFFF00", "background_color": "#000000", "background_enabled": 1,
+            "outline_width": 3.0, "outline_color": "#000000",
+            "shadow_offset_x": 0, "shadow_offset_y": 2, "shadow_blur": 4, "shadow_color": "#00000080",
+            "position": "bottom", "position_percentage": 80, "max_width": 90,
+            "animation_style": "none", "line_height": 1.2, "letter_spacing": 0, "text_align": "center",
+            "text_offset_x": 0, "text_offset_y": 0, "padding": 8, "border_radius": 4, "word_spacing": 0,
+            "created_at": 1, "updated_at": 2
+        })
+    }
+
+    #[test]
+    fn test_migrates_database_row() {
+        let preset = parse_subtitle_preset(&v1_row().to_string()).unwrap();
+        assert_eq!(preset.version, SUBTITLE_PRESET_VERSION);
+        assert_eq!(preset.name, "Bold Yellow");
+        assert!(preset.settings.background_enabled);
+        assert_eq!(preset.settings.border1_width, 3.0);
+        assert_eq!(preset.settings.border2_width, 0.0);
+        assert_eq!(preset.settings.font_weight, 800);
+        assert_eq!(preset.settings.animation_style, "pop");
+    }
+
+    #[test]
+    fn test_roundtrip_and_validation() {
+        let settings = parse_subtitle_preset(&v1_row().to_string()).unwrap().settings;
+        let file = SubtitlePresetFile { version: 2, name: "x".to_string(), description: None, settings };
+        let json = serde_json::to_string(&file).unwrap();
+        assert_eq!(parse_subtitle_preset(&json).unwrap().settings.text_color, "#FFFF00");
+
+        let mut row = v1_row();
+        row["text_color"] = Value::from("yellow");
+        row["font_weight"] = Value::from(1000);
+        let err = parse_subtitle_preset(&row.to_string()).unwrap_err();
+        assert!(err.contains("text color 'yellow'") && err.contains("font weight"));
+
+        let future = serde_json::json!({ "version": 99 }).to_string();
+        assert!(parse_subtitle_preset(&future).unwrap_err().contains("newer"));
+    }
+}
@@ -0,0 +1,161 @@
+use super::font_manager::get_fonts_dir;
+use super::subtitle::{generate_ass_file, generate_text_overlay_ass_file};
+use super::speech_enhancement::{speech_enhancement_filter, MAX_PREVIEW_SECS};
+use super::types::{SpeechEnhancementPreview, SpeechEnhancementPreviewRequest, SubtitlePreviewRequest};
+use super::video_info::{get_video_info, parse_aspect_ratio};
+use super::video_processor::{render_audio_sample, render_subtitle_frame};
+
+// Subtitle preset previews: one PNG frame with the same ASS output a build would burn in,
+// so the preview shows the real export appearance instead of the player's CSS approximation.
+
+// Source time shown at clip_time (clip timeline without intro: segments played back to back)
+pub fn source_time_at(segments: &[serde_json::Value], clip_time: f64) -> Option<f64> {
+    let mut elapsed = 0.0;
+    for segment in segments {
+        let start = segment["start_time"].as_f64()?;
+        let end = segment["end_time"].as_f64()?;
+        let duration = end - start;
+        if clip_time < elapsed + duration {
+            return Some(start + (clip_time - elapsed).max(0.0));
+        }
+        elapsed += duration;
+    }
+    None
+}
+
+// Render the preview frame into the temp folder and return its path
+pub async fn render_subtitle_preview(
+    app: &tauri::AppHandle,
+    request: &SubtitlePreviewRequest
+) -> Result<std::path::PathBuf, String> {
+    let intro_duration = request.intro_duration.unwrap_or(0.0);
+    if request.time < intro_duration {
+        return Err("Preview time is inside the intro, which has no captions".to_string());
+    }
+    let source_time = source_time_at(&request.segments, request.time - intro_duration)
+        .ok_or_else(|| format!("Preview time {:.2}s is past the end of the clip", request.time))?;
+    let aspect_ratio = parse_aspect_ratio(&request.aspect_ratio)?;
+
+    let paths = crate::storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let preview_dir = paths.temp.join("subtitle_previews");
+    // Only the latest preview is kept
+    let _ = std::fs::remove_dir_all(&preview_dir);
+    std::fs::create_dir_all(&preview_dir)
+        .map_err(|e| format!("Failed to create preview directory: {}", e))?;
+
+    // Unique name so the webview doesn't show a cached image
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let ratio_suffix = request.aspect_ratio.replace(':', "-");
+    let output_path = preview_dir.join(format!("preview_{}_{}.png", ratio_suffix, stamp));
+
+    // Subtitle files exactly as the build generates them
+    let video_info = get_video_info(app, &request.video_path).await?;
+    let fonts_dir = get_fonts_dir(app).ok();
+    let mut subtitle_paths = Vec::new();
+    if request.subtitle_settings.enabled {
+        let sub_path = preview_dir.join(format!("subtitles_{}.ass", ratio_suffix));
+        generate_ass_file(
+            &request.subtitle_settings,
+            &request.transcript_words,
+            &request.segments,
+            &sub_path,
+            request.max_words.unwrap_or(4),
+            Some(&aspect_ratio),
+            video_info.width,
+            video_info.height,
+            fonts_dir.as_deref(),
+            intro_duration
+        ).map_err(|e| format!("Failed to generate subtitle file: {}", e))?;
+        subtitle_paths.push(sub_path);
+    }
+    if !request.text_overlays.is_empty() {
+        let overlay_path = preview_dir.join(format!("text_overlays_{}.ass", ratio_suffix));
+        generate_text_overlay_ass_file(
+            &request.text_overlays,
+            &overlay_path,
+            Some(&aspect_ratio),
+            video_info.width,
+            video_info.height,
+            fonts_dir.as_deref()
+        ).map_err(|e| format!("Failed to generate text overlay file: {}", e))?;
+        subtitle_paths.push(overlay_path);
+    }
+
+    println!("[Rust] Rendering subtitle preview at {:.2}s (source {:.2}s) for {}", request.time, source_time, request.aspect_ratio);
+    let result = render_subtitle_frame(
+        app,
+        &request.video_path,
+        source_time,
+        request.time,
+        &aspect_ratio,
+        &subtitle_paths,
+        &output_path
+    ).await;
+
+    for path in subtitle_paths {
+        let _ = std::fs::remove_file(path);
+    }
+    result.map(|_| output_path)
+}
+
+// Render the same stretch of audio with and without speech enhancement, so both can be played
+// back to compare
+pub async fn render_speech_enhancement_preview(
+    app: &tauri::AppHandle,
+    request: &SpeechEnhancementPreviewRequest
+) -> Result<SpeechEnhancementPreview, String> {
+    let duration = request.duration.unwrap_or(10.0).clamp(1.0, MAX_PREVIEW_SECS);
+    let filter = speech_enhancement_filter(&request.settings);
+
+    let paths = crate::storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let preview_dir = paths.temp.join("enhancement_previews");
+    // Only the latest preview is kept
+    let _ = std::fs::remove_dir_all(&preview_dir);
+    std::fs::create_dir_all(&preview_dir)
+        .map_err(|e| format!("Failed to create preview directory: {}", e))?;
+
+    // Unique names so the webview doesn't play a cached file
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let original_path = preview_dir.join(format!("original_{}.wav", stamp));
+    let enhanced_path = preview_dir.join(format!("enhanced_{}.wav", stamp));
+
+    println!("[Rust] Rendering speech enhancement preview at {:.2}s ({:.1}s): {}",
+        request.start_time, duration, filter.as_deref().unwrap_or("no filters"));
+    let (original, enhanced) = futures::future::join(
+        render_audio_sample(app, &request.video_path, request.start_time, duration, None, &original_path),
+        render_audio_sample(app, &request.video_path, request.start_time, duration, filter.as_deref(), &enhanced_path)
+    ).await;
+    original?;
+    enhanced?;
+
+    Ok(SpeechEnhancementPreview {
+        original_path: original_path.to_string_lossy().to_string(),
+        enhanced_path: enhanced_path.to_string_lossy().to_string(),
+        filter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_time_at() {
+        let segments = vec![
+            serde_json::json!({ "start_time": 10.0, "end_time": 12.0 }),
+            serde_json::json!({ "start_time": 30.0, "end_time": 35.0 }),
+        ];
+        assert_eq!(source_time_at(&segments, 0.5), Some(10.5));
+        assert_eq!(source_time_at(&segments, 2.0), Some(30.0));
+        assert_eq!(source_time_at(&segments, 6.5), Some(34.5));
+        assert_eq!(source_time_at(&segments, 7.0), None);
+    }
+}
@@ -0,0 +1,47 @@
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::encoder::EncoderConfig;
+
+// Global CPU budget shared by every FFmpeg process spawned for clip builds.
+// Each job acquires as many permits as the threads it is allowed to use, so parallel
+// aspect ratios, segments and batch clips never oversubscribe the machine.
+static FFMPEG_CPU_BUDGET: Lazy<Arc<Semaphore>> = Lazy::new(|| Arc::new(Semaphore::new(cpu_budget() as usize)));
+
+// Total number of CPU "slots" available for FFmpeg work
+pub fn cpu_budget() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(4)
+        .max(1)
+}
+
+// Number of threads an encode with this encoder should use (and therefore the permits it costs)
+pub fn encode_job_cost(encoder: &EncoderConfig) -> u32 {
+    let budget = cpu_budget();
+    let cost = if encoder.codec == "libx264" {
+        // Software encoding is CPU bound - give each job a fair share of the machine
+        (budget / 2).clamp(2, 8)
+    } else {
+        // Hardware encoders mostly need CPU for decoding and filtering
+        2
+    };
+    cost.min(budget)
+}
+
+// Cost of a stream-copy job (concat, remux) - these are I/O bound
+pub fn copy_job_cost() -> u32 {
+    1
+}
+
+// Wait until the requested number of CPU slots is free. The slots are released when the
+// returned permit is dropped, so hold it for exactly as long as the FFmpeg process runs.
+pub async fn acquire_ffmpeg_slots(cost: u32) -> OwnedSemaphorePermit {
+    let cost = cost.clamp(1, cpu_budget());
+    FFMPEG_CPU_BUDGET
+        .clone()
+        .acquire_many_owned(cost)
+        .await
+        .expect("FFmpeg CPU budget semaphore should never be closed")
+}
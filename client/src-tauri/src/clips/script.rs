@@ -0,0 +1,124 @@
+// Writing-system helpers for captions in languages other than English: CJK text has no
+// spaces between words (and may break between any two characters), RTL scripts need the
+// line laid out right to left.
+
+// Han, kana, Hangul and CJK punctuation/fullwidth forms
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x2FDF      // CJK radicals
+        | 0x3000..=0x303F    // CJK symbols and punctuation
+        | 0x3040..=0x30FF    // Hiragana, Katakana
+        | 0x3100..=0x31FF    // Bopomofo, Hangul compatibility jamo, Katakana extensions
+        | 0x3400..=0x4DBF    // CJK extension A
+        | 0x4E00..=0x9FFF    // CJK unified ideographs
+        | 0xAC00..=0xD7AF    // Hangul syllables
+        | 0xF900..=0xFAFF    // CJK compatibility ideographs
+        | 0xFF00..=0xFFEF    // Halfwidth and fullwidth forms
+        | 0x20000..=0x2FA1F  // CJK extensions B-F
+    )
+}
+
+// Korean is written with spaces between words, unlike Chinese and Japanese
+fn is_hangul(c: char) -> bool {
+    matches!(c as u32, 0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF)
+}
+
+// Strong right-to-left characters (Hebrew, Arabic, Syriac, Thaana, NKo and their presentation forms)
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF
+        | 0xFB1D..=0xFDFF
+        | 0xFE70..=0xFEFF
+        | 0x10800..=0x10FFF
+        | 0x1E800..=0x1EFFF
+    )
+}
+
+// Whether the text is predominantly right-to-left (counted over letters only)
+pub fn is_rtl_text<'a>(words: impl IntoIterator<Item = &'a str>) -> bool {
+    let (mut rtl, mut ltr) = (0usize, 0usize);
+    for c in words.into_iter().flat_map(|w| w.chars()).filter(|c| c.is_alphabetic()) {
+        if is_rtl_char(c) {
+            rtl += 1;
+        } else {
+            ltr += 1;
+        }
+    }
+    rtl > ltr
+}
+
+// Whether a space goes between two adjacent caption words (not between Chinese/Japanese characters)
+pub fn needs_space_between(previous: &str, next: &str) -> bool {
+    let unspaced = |c: Option<char>| c.map(|c| is_cjk(c) && !is_hangul(c)).unwrap_or(false);
+    !(unspaced(previous.trim().chars().last()) && unspaced(next.trim().chars().next()))
+}
+
+// Join caption words with spaces where the script uses them
+pub fn join_caption_words<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&str> = None;
+    for word in words {
+        let word = word.trim();
+        if let Some(previous) = previous {
+            if needs_space_between(previous, word) {
+                text.push(' ');
+            }
+        }
+        text.push_str(word);
+        previous = Some(word);
+    }
+    text
+}
+
+// Split a word into leading punctuation, the word itself, and trailing punctuation
+pub fn split_punctuation(word: &str) -> (&str, &str, &str) {
+    let trimmed = word.trim();
+    let core_start = trimmed.find(|c: char| c.is_alphanumeric()).unwrap_or(trimmed.len());
+    let core_end = trimmed
+        .rfind(|c: char| c.is_alphanumeric())
+        .map(|i| i + trimmed[i..].chars().next().map(|c| c.len_utf8()).unwrap_or(1))
+        .unwrap_or(core_start);
+    (&trimmed[..core_start], &trimmed[core_start..core_end], &trimmed[core_end..])
+}
+
+// Split caption text into timing tokens: whitespace-separated words, with Chinese/Japanese runs
+// split into single characters (trailing punctuation stays on the character before it)
+pub fn split_caption_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        let mut current = String::new();
+        for c in word.chars() {
+            let unspaced = is_cjk(c) && !is_hangul(c);
+            let punctuation = !c.is_alphanumeric();
+            let current_unspaced = current.chars().last().map(|p| is_cjk(p) && !is_hangul(p)).unwrap_or(false);
+            if !current.is_empty() && !punctuation && (unspaced || current_unspaced) {
+                tokens.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cjk_tokens_and_joining() {
+        assert_eq!(split_caption_tokens("我们走吧。 ok"), vec!["我", "们", "走", "吧。", "ok"]);
+        assert_eq!(split_caption_tokens("hello world"), vec!["hello", "world"]);
+        assert_eq!(join_caption_words(["我", "们", "ok", "fine"]), "我们 ok fine");
+        assert_eq!(join_caption_words(["안녕", "하세요"]), "안녕 하세요");
+    }
+
+    #[test]
+    fn test_rtl_detection() {
+        assert!(is_rtl_text(["שלום", "עולם"]));
+        assert!(is_rtl_text(["مرحبا", "YouTube", "بكم"]));
+        assert!(!is_rtl_text(["hello", "world"]));
+    }
+}
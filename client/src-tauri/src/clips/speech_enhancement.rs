@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use super::types::{NoiseReduction, SpeechEnhancement};
+
+// Speech enhancement: an FFmpeg audio filter chain (high-pass, denoiser, de-esser, compressor)
+// applied to clip audio in the final encode and to audio extracted for transcription.
+
+// Longest preview sample
+pub const MAX_PREVIEW_SECS: f64 = 20.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechEnhancementPreset {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub settings: SpeechEnhancement,
+}
+
+fn preset(id: &str, name: &str, description: &str, settings: SpeechEnhancement) -> SpeechEnhancementPreset {
+    SpeechEnhancementPreset {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        settings,
+    }
+}
+
+// Named presets offered in the clip builder and before transcription
+pub fn speech_enhancement_presets() -> Vec<SpeechEnhancementPreset> {
+    vec![
+        preset("light", "Light cleanup", "Removes hum and mic hiss, otherwise leaves the voice untouched", SpeechEnhancement {
+            highpass_hz: 80,
+            noise_reduction: NoiseReduction::Fft,
+            noise_reduction_db: 10.0,
+            ..Default::default()
+        }),
+        preset("voice", "Clear voice", "Hiss removal, softer sibilants and even loudness for talking to camera", SpeechEnhancement {
+            highpass_hz: 100,
+            noise_reduction: NoiseReduction::Fft,
+            noise_reduction_db: 14.0,
+            deesser: 0.4,
+            compressor: true,
+        }),
+        preset("stream", "Game stream", "Pushes steady game and fan noise behind the voice down", SpeechEnhancement {
+            highpass_hz: 100,
+            noise_reduction: NoiseReduction::Fft,
+            noise_reduction_db: 18.0,
+            deesser: 0.3,
+            compressor: true,
+        }),
+        preset("transcription", "Transcription", "Strongest cleanup for speech recognition, not meant for listening", SpeechEnhancement {
+            highpass_hz: 120,
+            noise_reduction: NoiseReduction::Fft,
+            noise_reduction_db: 24.0,
+            compressor: true,
+            ..Default::default()
+        }),
+    ]
+}
+
+// Audio filter chain (-af) for the settings, None when every stage is off
+pub fn speech_enhancement_filter(settings: &SpeechEnhancement) -> Option<String> {
+    let mut filters = Vec::new();
+    if settings.highpass_hz > 0 {
+        filters.push(format!("highpass=f={}", settings.highpass_hz.min(1000)));
+    }
+    match settings.noise_reduction {
+        NoiseReduction::None => {}
+        // Noise floor tracking follows changing background noise
+        NoiseReduction::Fft => filters.push(format!(
+            "afftdn=nr={:.1}:nf=-50:tn=1",
+            settings.noise_reduction_db.clamp(1.0, 97.0)
+        )),
+    }
+    if settings.deesser > 0.0 {
+        filters.push(format!("deesser=i={:.2}", settings.deesser.min(1.0)));
+    }
+    if settings.compressor {
+        // 3:1 above -21 dB, +6 dB makeup
+        filters.push("acompressor=threshold=0.089:ratio=3:attack=5:release=120:makeup=2".to_string());
+    }
+    (!filters.is_empty()).then(|| filters.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_chain() {
+        assert_eq!(speech_enhancement_filter(&SpeechEnhancement::default()), None);
+
+        let stream = speech_enhancement_presets().into_iter().find(|p| p.id == "stream").unwrap();
+        assert_eq!(
+            speech_enhancement_filter(&stream.settings).unwrap(),
+            "highpass=f=100,afftdn=nr=18.0:nf=-50:tn=1,deesser=i=0.30,\
+             acompressor=threshold=0.089:ratio=3:attack=5:release=120:makeup=2"
+        );
+    }
+
+    #[test]
+    fn test_preset_ids_are_unique() {
+        let presets = speech_enhancement_presets();
+        let mut ids: Vec<&str> = presets.iter().map(|p| p.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), presets.len());
+    }
+}
@@ -1,22 +1,35 @@
 use std::io::Write;
-use super::types::{SubtitleSettings, WordInfo, AspectRatio};
+use super::types::{SubtitleSettings, WordInfo, AspectRatio, TextOverlayItem};
+use super::captions::{build_clip_timeline, ClipWord};
+use super::caption_chunker::chunk_words;
+use super::animation::{render_active_word_boxes, render_chunk, render_page_background, BoxStyle, AnimationStyle, AssEvent, ChunkContext, WordEmphasis};
+use super::script::is_rtl_text;
+use super::font_registry::font_registry;
+use super::decoration::{decorated_ass_text, decorated_text, CaptionDecorator, WordDecoration};
+use super::text_metrics::{layout_words, line_starts, space_width, FontMetrics, LineLayoutParams};
 
 // Helper to embed fonts directly in ASS file
 pub fn embed_fonts_in_ass(
     file: &mut std::fs::File,
-    fonts_dir: &std::path::Path,
+    fonts_dir: Option<&std::path::Path>,
     settings: &SubtitleSettings
+) -> Result<(), String> {
+    // Embed the face the settings resolve to (bundled or user font)
+    embed_font_files_in_ass(file, required_font_files(settings, fonts_dir))
+}
+
+// Embed a list of font files in an ASS file
+fn embed_font_files_in_ass(
+    file: &mut std::fs::File,
+    font_files_to_embed: Vec<std::path::PathBuf>
 ) -> Result<(), String> {
     use std::io::Read;
     
-    // Determine which font files we need based on font family and weight
-    let font_files_to_embed = get_required_font_files(settings);
-    
     // ASS fonts section uses UUencoded format
     writeln!(file, "[Fonts]").unwrap();
     
-    for font_filename in font_files_to_embed {
-        let font_path = fonts_dir.join(&font_filename);
+    for font_path in font_files_to_embed {
+        let font_filename = font_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
         
         if !font_path.exists() {
             println!("[Rust] Warning: Font file not found: {} - FFmpeg will use system fallback", font_path.display());
@@ -53,51 +66,34 @@ pub fn embed_fonts_in_ass(
     Ok(())
 }
 
-// Helper to get required font files for embedding
-fn get_required_font_files(settings: &SubtitleSettings) -> Vec<String> {
-    let mut files = Vec::new();
-    
-    // Determine font file based on family and weight
-    let weight_suffix = if settings.font_weight >= 700 {
-        "Bold"
-    } else if settings.font_weight >= 600 {
-        "SemiBold"
-    } else if settings.font_weight >= 500 {
-        "Medium"
-    } else if settings.font_weight < 400 && settings.font_weight >= 300 {
-        "Light"
-    } else if settings.font_weight < 300 {
-        "Thin"
-    } else {
-        "Regular"
-    };
-    
-    // Build font filename
-    let font_file = match settings.font_family.as_str() {
-        "Open Sans" => {
-            // Handle space in name
-            if weight_suffix == "Regular" {
-                "OpenSans-Regular.ttf".to_string()
-            } else {
-                format!("OpenSans-{}.ttf", weight_suffix)
+// Font files the settings render with: the registry face for the family at the nearest weight/style
+fn required_font_files(settings: &SubtitleSettings, fonts_dir: Option<&std::path::Path>) -> Vec<std::path::PathBuf> {
+    let registry = font_registry(fonts_dir);
+    match registry.resolve(&settings.font_family, settings.font_weight, settings.italic) {
+        Some(face) => {
+            if face.weight != settings.font_weight || face.italic != settings.italic {
+                println!("[Rust] WARNING: {} {}{} not available, using {}",
+                    settings.font_family, settings.font_weight, if settings.italic { " italic" } else { "" }, face.path.display());
             }
+            vec![face.path.clone()]
         },
-        "Bebas Neue" => {
-            // Bebas Neue only has Regular weight
-            "BebasNeue-Regular.ttf".to_string()
-        },
-        _ => {
-            // Standard format: FontName-Weight.ttf
-            if weight_suffix == "Regular" {
-                format!("{}-Regular.ttf", settings.font_family)
-            } else {
-                format!("{}-{}.ttf", settings.font_family, weight_suffix)
-            }
+        None => {
+            println!("[Rust] WARNING: Font family not installed: {} - FFmpeg will use a fallback font", settings.font_family);
+            Vec::new()
         }
-    };
-    
-    files.push(font_file);
-    files
+    }
+}
+
+// Metrics of the font the settings render with, for measuring caption text
+fn load_font_metrics(settings: &SubtitleSettings, fonts_dir: Option<&std::path::Path>) -> Option<FontMetrics> {
+    let font_path = required_font_files(settings, fonts_dir).into_iter().next()?;
+    match FontMetrics::load(&font_path) {
+        Ok(metrics) => Some(metrics),
+        Err(e) => {
+            println!("[Rust] WARNING: Using approximate text measurement: {}", e);
+            None
+        }
+    }
 }
 
 // UUencode data for ASS font embedding (ASS uses UUencoding, not base64)
@@ -162,59 +158,378 @@ pub fn generate_ass_file(
     let play_res_y = 1080;
     let play_res_x = (video_width as f64 * (1080.0 / video_height as f64)).round() as u32;
 
-    writeln!(file, "[Script Info]").unwrap();
-    writeln!(file, "ScriptType: v4.00+").unwrap();
-    writeln!(file, "PlayResX: {}", play_res_x).unwrap();
-    writeln!(file, "PlayResY: {}", play_res_y).unwrap();
-    writeln!(file, "WrapStyle: 1").unwrap(); // Word wrapping
-    writeln!(file, "ScaledBorderAndShadow: yes").unwrap();
-    writeln!(file, "").unwrap();
+    write_script_info(&mut file, play_res_x, play_res_y);
     
-    // Embed fonts if available
-    if let Some(fonts_path) = fonts_dir {
-        println!("[Rust] Attempting to embed fonts from: {}", fonts_path.display());
-        if fonts_path.exists() {
-            println!("[Rust] Fonts directory exists, embedding...");
-            embed_fonts_in_ass(&mut file, fonts_path, settings)?;
-        } else {
-            println!("[Rust] WARNING: Fonts directory does not exist! Fonts will not be embedded.");
-        }
-    } else {
-        println!("[Rust] WARNING: No fonts directory provided, fonts will not be embedded.");
+    // Embed the caption font (bundled or user-installed)
+    if !fonts_dir.map(|p| p.exists()).unwrap_or(false) {
+        println!("[Rust] WARNING: Bundled fonts directory not found, only user fonts can be embedded.");
     }
+    embed_fonts_in_ass(&mut file, fonts_dir, settings)?;
     
     writeln!(file, "").unwrap();
 
     // Generate Style
-    writeln!(file, "[V4+ Styles]").unwrap();
-    writeln!(file, "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding").unwrap();
+    write_styles_header(&mut file);
 
-    // Convert colors (Hex #RRGGBB to &HAABBGGRR with alpha)
-    // ASS format: &HAABBGGRR where AA is alpha (00=opaque, FF=transparent)
-    let convert_color = |hex: &str| -> String {
-        let hex = hex.trim_start_matches('#');
-        if hex.len() >= 6 {
-            let r = &hex[0..2];
-            let g = &hex[2..4];
-            let b = &hex[4..6];
-            // ASS uses BGR order with alpha prefix (00 = fully opaque)
-            // Note: Style definitions do NOT use the trailing '&' that override tags use
-            format!("&H00{}{}{}", b, g, r).to_uppercase()
-        } else {
-            "&H00FFFFFF".to_string()
+    // 1. Flatten words relative to clip timeline
+    let clip_timeline_words = build_clip_timeline(all_words, clip_segments, time_offset);
+
+    let font_metrics = load_font_metrics(settings, fonts_dir);
+    // Text direction comes from the words this clip shows
+    let rtl = is_rtl_text(clip_timeline_words.iter().map(|w| w.word.as_str()));
+    let animation = AnimationStyle::from_setting(&settings.animation_style);
+    println!("[Rust] Subtitle animation: {:?}", animation);
+
+    // The base style, plus one style per speaker ("Speaker1Border2Layer", ...)
+    let mut style_sets: Vec<CaptionStyleSet> = Vec::new();
+    let speaker_styles = std::iter::once(None).chain(settings.speaker_styles.iter().map(Some));
+    for (i, speaker_style) in speaker_styles.enumerate() {
+        let style_settings = match speaker_style {
+            Some(style) => settings.for_speaker(style),
+            None => settings.clone(),
+        };
+        let prefix = if i == 0 { String::new() } else { format!("Speaker{}", i) };
+
+        let mut layout = compute_style_layout(&style_settings, aspect_ratio, play_res_x, play_res_y, font_metrics.as_ref());
+        layout.rtl = rtl;
+        let mut line_params = line_layout_params(&layout, play_res_x, font_metrics.as_ref());
+        if let Some(position_x) = speaker_style.and_then(|style| style.position_x) {
+            place_near_speaker(&mut layout, &mut line_params, position_x, play_res_x);
         }
-    };
 
-    let primary_color = convert_color(&settings.text_color);
-    let border1_color = convert_color(&settings.border1_color);
-    let border2_color = convert_color(&settings.border2_color);
-    let _back_color = convert_color(&settings.background_color);
+        write_layer_styles(&mut file, &prefix, &style_settings, &layout);
+        if animation.needs_highlight_box_style() {
+            write_highlight_box_style(&mut file, &prefix, &style_settings, &layout);
+        }
+
+        style_sets.push(CaptionStyleSet {
+            speaker: speaker_style.map(|style| style.speaker.as_str()),
+            prefix,
+            emphasis: word_emphasis(&style_settings),
+            active_box: active_box_style(&style_settings, &layout),
+            background_box: background_box_style(&style_settings, &layout),
+            layout,
+            line_params,
+        });
+    }
 
+    let base_layout = &style_sets[0].layout;
     println!("[Rust] Subtitle colors - Text: {}, Border1: {}, Border2: {}, Background: {}", 
         settings.text_color, settings.border1_color, settings.border2_color, settings.background_color);
-    println!("[Rust] ASS colors - Primary: {}, Border1: {}, Border2: {}", primary_color, border1_color, border2_color);
+    println!("[Rust] ASS colors - Primary: {}, Border1: {}, Border2: {}", base_layout.primary_color, base_layout.border1_color, base_layout.border2_color);
     println!("[Rust] Using font: {}", settings.font_family);
+    println!("[Rust] Font size: {} -> {} (scale: {})", settings.font_size, base_layout.font_size, base_layout.font_size_scale);
+    if style_sets.len() > 1 {
+        println!("[Rust] Speaker caption styles: {}", style_sets.len() - 1);
+    }
+
+    // Keyword emphasis/emoji rules
+    let decorator = CaptionDecorator::new(&settings.decorations)?;
+    if settings.decorations.iter().any(|rule| rule.emoji.is_some()) {
+        warn_if_emoji_font_missing(fonts_dir);
+    }
+
+    // Decorations are drawn below the text: page background first, then the active word box
+    let has_background = style_sets.iter().any(|set| set.background_box.is_some());
+    let has_active_box = style_sets.iter().any(|set| set.active_box.is_some());
+    let active_box_layer = if has_background { 1 } else { 0 };
+    let layer_offset = active_box_layer + if has_active_box { 1 } else { 0 };
+
+    write_events_header(&mut file);
+
+    if clip_timeline_words.is_empty() {
+        return Ok(());
+    }
+
+    // 2. Group words into chunks (pages) and generate events for each chunk with the animation style
+    let chunks = chunk_words(&clip_timeline_words, &settings.chunking, max_words);
+    println!("[Rust] Caption pages: {} ({} words)", chunks.len(), clip_timeline_words.len());
+
+    for (i, range) in chunks.into_iter().enumerate() {
+        let start_idx = range.start;
+        let chunk = &clip_timeline_words[range];
+
+        let chunk_visible_start = if i == 0 {
+            time_offset  // Start at the time offset (after intro)
+        } else {
+            clip_timeline_words[start_idx - 1].end
+        };
+        
+        let chunk_visible_end = chunk.last().unwrap().end;
+
+        // Pages never span speakers, so the first word picks the style
+        let style = style_sets
+            .iter()
+            .find(|set| set.speaker.is_some() && set.speaker == chunk[0].speaker.as_deref())
+            .unwrap_or(&style_sets[0]);
+
+        // Break the page into balanced lines ourselves so text and decorations share one layout
+        let decorations: Vec<Option<WordDecoration>> = chunk
+            .iter()
+            .map(|w| if decorator.is_empty() { None } else { decorator.match_word(&w.word) })
+            .collect();
+        let page_text: Vec<String> = chunk.iter().zip(&decorations).map(|(w, d)| decorated_text(&w.word, d.as_ref())).collect();
+        let page_words: Vec<&str> = page_text.iter().map(|w| w.as_str()).collect();
+        let word_boxes = layout_words(&page_words, &style.line_params);
+
+        // Decoration tags and the emoji font switch only go into the rendered text (the layout measured the plain text)
+        let display_words: Vec<ClipWord> = chunk
+            .iter()
+            .zip(&decorations)
+            .map(|(w, d)| ClipWord {
+                word: decorated_ass_text(&w.word, d.as_ref(), &settings.font_family, &style.emphasis.base_color),
+                ..w.clone()
+            })
+            .collect();
+
+        let ctx = ChunkContext {
+            words: &display_words,
+            visible_start: chunk_visible_start,
+            visible_end: chunk_visible_end,
+            style_prefix: &style.prefix,
+            pos_x: style.layout.pos_x,
+            pos_y: style.layout.pos_y,
+            font_size: style.layout.font_size,
+            // Use \fw tag to ensure correct font weight
+            weight_tag: format!("{{\\fw{}}}", settings.font_weight),
+            word_separator: style.layout.word_separator(),
+            line_starts: line_starts(&word_boxes),
+            emphasis: style.emphasis.clone(),
+        };
+
+        if let Some(event) = style.background_box.as_ref().and_then(|box_style| render_page_background(&ctx, &word_boxes, box_style)) {
+            write_event(&mut file, &event);
+        }
+        if let Some(box_style) = &style.active_box {
+            for mut event in render_active_word_boxes(&ctx, &word_boxes, box_style) {
+                event.layer += active_box_layer;
+                write_event(&mut file, &event);
+            }
+        }
+
+        for mut event in render_chunk(animation, &ctx) {
+            event.layer += layer_offset;
+            write_event(&mut file, &event);
+        }
+    }
+
+    Ok(())
+}
+
+// Caption style resolved for the base settings or one speaker's overrides
+struct CaptionStyleSet<'a> {
+    // None for the base style
+    speaker: Option<&'a str>,
+    prefix: String,
+    layout: AssStyleLayout,
+    line_params: LineLayoutParams<'a>,
+    emphasis: WordEmphasis,
+    active_box: Option<BoxStyle>,
+    background_box: Option<BoxStyle>,
+}
+
+// Center captions at position_x (percent of the frame width), narrowing the lines so they stay on screen
+fn place_near_speaker(layout: &mut AssStyleLayout, line_params: &mut LineLayoutParams, position_x: f32, play_res_x: u32) {
+    let frame_width = play_res_x as f64;
+    let padding = layout.padding as f64;
+    let x = frame_width * (position_x as f64 / 100.0).clamp(0.0, 1.0);
+    let room = 2.0 * (x.min(frame_width - x) - padding);
+    // Keep at least a few words per line when the speaker is near the edge
+    line_params.max_line_width = line_params.max_line_width.min(room).max(layout.font_size as f64 * 4.0);
+    let half_width = (line_params.max_line_width / 2.0 + padding).min(frame_width / 2.0);
+    layout.pos_x = x.clamp(half_width, frame_width - half_width);
+    line_params.center_x = layout.pos_x;
+}
+
+// Font family used for emoji runs (NotoEmoji-Regular.ttf in the fonts directory, see fonts/README.md).
+// The outline Noto Emoji, not Noto Color Emoji: libass can't rasterize color bitmap (CBDT) glyphs.
+pub const EMOJI_FONT_FAMILY: &str = "Noto Emoji";
+pub const EMOJI_FONT_FILE: &str = "NotoEmoji-Regular.ttf";
+
+// Emoji render as blank boxes (or a random system font) without the bundled emoji font
+fn warn_if_emoji_font_missing(fonts_dir: Option<&std::path::Path>) {
+    if !fonts_dir.map(|dir| dir.join(EMOJI_FONT_FILE).exists()).unwrap_or(false) {
+        println!("[Rust] WARNING: {} not found in the fonts directory, caption emoji may not render", EMOJI_FONT_FILE);
+    }
+}
 
+// Style values for one SubtitleSettings, resolved to the ASS coordinate system
+pub struct AssStyleLayout {
+    pub font_size_scale: f32,
+    pub font_size: f32,
+    pub border1_width: f32,
+    pub border2_width: f32,
+    pub shadow: f32,
+    pub letter_spacing: f32,
+    pub padding: f32,
+    pub margin_l: i32,
+    pub margin_r: i32,
+    pub margin_v: i32,
+    pub alignment: i32,
+    pub bold: i32,
+    pub italic: i32,
+    pub pos_x: f64,
+    pub pos_y: f64,
+    pub primary_color: String,
+    pub border1_color: String,
+    pub border2_color: String,
+    pub shadow_color: String,
+    pub space_char_spacing: f32,
+    // Distance between caption lines
+    pub line_height: f32,
+    // Right-to-left text: libass picks the base direction itself (Encoding -1) and lines are laid out mirrored
+    pub rtl: bool,
+}
+
+impl AssStyleLayout {
+    pub fn pos_tag(&self) -> String {
+        format!("{{\\pos({:.0},{:.0})}}", self.pos_x, self.pos_y)
+    }
+
+    // Style Encoding field (-1 = detect the base text direction)
+    pub fn encoding(&self) -> i32 {
+        if self.rtl { -1 } else { 1 }
+    }
+
+    // Separator: Set spacing for space char, then space char, then reset spacing for next word
+    pub fn word_separator(&self) -> String {
+        format!("{{\\fsp{:.1}}} {{\\fsp{:.1}}}", self.space_char_spacing, self.letter_spacing)
+    }
+}
+
+// Convert colors (Hex #RRGGBB to &HAABBGGRR with alpha)
+// ASS format: &HAABBGGRR where AA is alpha (00=opaque, FF=transparent)
+pub fn to_ass_color(hex: &str) -> String {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() >= 6 {
+        let r = &hex[0..2];
+        let g = &hex[2..4];
+        let b = &hex[4..6];
+        // ASS uses BGR order with alpha prefix (00 = fully opaque)
+        // Note: Style definitions do NOT use the trailing '&' that override tags use
+        format!("&H00{}{}{}", b, g, r).to_uppercase()
+    } else {
+        "&H00FFFFFF".to_string()
+    }
+}
+
+// Convert a hex color to an override tag color (&HBBGGRR&)
+pub fn to_ass_override_color(hex: &str) -> String {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() >= 6 {
+        format!("&H{}{}{}&", &hex[4..6], &hex[2..4], &hex[0..2]).to_uppercase()
+    } else {
+        "&HFFFFFF&".to_string()
+    }
+}
+
+// Alpha of a #RRGGBBAA color as an override tag alpha (opaque for #RRGGBB)
+pub fn to_ass_hex_alpha(hex: &str) -> String {
+    let hex = hex.trim_start_matches('#');
+    let opacity = if hex.len() == 8 {
+        u8::from_str_radix(&hex[6..8], 16).map(|a| a as f32 / 255.0).unwrap_or(1.0)
+    } else {
+        1.0
+    };
+    to_ass_alpha(opacity)
+}
+
+// Convert an opacity (0-1) to an override tag alpha (&HAA&, 00 = opaque)
+pub fn to_ass_alpha(opacity: f32) -> String {
+    format!("&H{:02X}&", ((1.0 - opacity.clamp(0.0, 1.0)) * 255.0).round() as u8)
+}
+
+// Active-word color and inactive-word dimming from the settings
+fn word_emphasis(settings: &SubtitleSettings) -> WordEmphasis {
+    WordEmphasis {
+        base_color: to_ass_override_color(&settings.text_color),
+        active_color: settings.active_word_color.as_deref().map(to_ass_override_color),
+        inactive_alpha: (settings.inactive_word_opacity < 1.0).then(|| to_ass_alpha(settings.inactive_word_opacity)),
+    }
+}
+
+// Box behind the active word, if an active box color is set
+fn active_box_style(settings: &SubtitleSettings, layout: &AssStyleLayout) -> Option<BoxStyle> {
+    let color = settings.active_box_color.as_deref()?;
+    let padding = (settings.active_box_padding * layout.font_size_scale) as f64;
+    Some(BoxStyle {
+        color: to_ass_override_color(color),
+        alpha: to_ass_hex_alpha(color),
+        padding,
+        radius: (settings.active_box_radius * layout.font_size_scale) as f64,
+        height: layout.font_size as f64,
+    })
+}
+
+// Background box behind each page (matches the preview's padded, rounded container)
+fn background_box_style(settings: &SubtitleSettings, layout: &AssStyleLayout) -> Option<BoxStyle> {
+    if !settings.background_enabled {
+        return None;
+    }
+    Some(BoxStyle {
+        color: to_ass_override_color(&settings.background_color),
+        alpha: to_ass_hex_alpha(&settings.background_color),
+        padding: layout.padding as f64,
+        radius: (settings.border_radius * layout.font_size_scale) as f64,
+        // Same line height the text layout uses
+        height: layout.line_height as f64,
+    })
+}
+
+// Line layout inputs matching the caption style (centered at \pos, wrapped inside the margins)
+fn line_layout_params<'a>(layout: &AssStyleLayout, play_res_x: u32, font: Option<&'a FontMetrics>) -> LineLayoutParams<'a> {
+    LineLayoutParams {
+        font_size: layout.font_size as f64,
+        letter_spacing: layout.letter_spacing as f64,
+        space_spacing: layout.space_char_spacing as f64,
+        max_line_width: (play_res_x as i32 - layout.margin_l - layout.margin_r).max(1) as f64,
+        center_x: layout.pos_x,
+        center_y: layout.pos_y,
+        line_height: layout.line_height as f64,
+        font,
+        rtl: layout.rtl,
+    }
+}
+
+// Format time to H:MM:SS.cc
+pub fn format_ass_time(t: f64) -> String {
+    let t = t.max(0.0);
+    let hours = (t / 3600.0).floor() as u32;
+    let mins = ((t % 3600.0) / 60.0).floor() as u32;
+    let secs = (t % 60.0).floor() as u32;
+    let centis = ((t % 1.0) * 100.0).round() as u32;
+    format!("{}:{:02}:{:02}.{:02}", hours, mins, secs, centis)
+}
+
+fn write_script_info(file: &mut std::fs::File, play_res_x: u32, play_res_y: u32) {
+    writeln!(file, "[Script Info]").unwrap();
+    writeln!(file, "ScriptType: v4.00+").unwrap();
+    writeln!(file, "PlayResX: {}", play_res_x).unwrap();
+    writeln!(file, "PlayResY: {}", play_res_y).unwrap();
+    writeln!(file, "WrapStyle: 1").unwrap(); // Word wrapping
+    writeln!(file, "ScaledBorderAndShadow: yes").unwrap();
+    writeln!(file).unwrap();
+}
+
+fn write_styles_header(file: &mut std::fs::File) {
+    writeln!(file, "[V4+ Styles]").unwrap();
+    writeln!(file, "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding").unwrap();
+}
+
+fn write_events_header(file: &mut std::fs::File) {
+    writeln!(file).unwrap();
+    writeln!(file, "[Events]").unwrap();
+    writeln!(file, "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text").unwrap();
+}
+
+// Resolve SubtitleSettings into ASS style values (matches VideoPlayer.vue layout)
+pub fn compute_style_layout(
+    settings: &SubtitleSettings,
+    aspect_ratio: Option<&AspectRatio>,
+    play_res_x: u32,
+    play_res_y: u32,
+    font: Option<&FontMetrics>
+) -> AssStyleLayout {
     // Calculate aspect ratio scaling (matches VideoPlayer.vue logic)
     let aspect_ratio_value = if let Some(ar) = aspect_ratio {
         ar.width / ar.height
@@ -243,340 +558,343 @@ pub fn generate_ass_file(
     // ASS Shadow parameter is an offset depth, calculate from shadow offset X/Y
     // Use the magnitude of the offset vector for proper shadow distance
     let shadow_offset_magnitude = ((settings.shadow_offset_x.powi(2) + settings.shadow_offset_y.powi(2)).sqrt()) * font_size_scale;
-    let adjusted_shadow = shadow_offset_magnitude;
     let adjusted_letter_spacing = settings.letter_spacing * font_size_scale;
 
-    println!("[Rust] Font size: {} -> {} (scale: {})", settings.font_size, adjusted_font_size, font_size_scale);
-
     // Calculate margins and positioning to match VideoPlayer.vue
     // Vue uses a container with width=maxWidth% centered on screen
     // And positions it using top=positionPercentage% and translate(-50%, -50%)
-    
     let adjusted_padding = settings.padding * font_size_scale;
     let box_width_px = play_res_x as f64 * (settings.max_width as f64 / 100.0);
-    
+
     // Calculate margins to constrain text to box_width - 2*padding
     // The box is centered on screen, so margins are symmetric
     let side_margin = (play_res_x as f64 - box_width_px) / 2.0;
     let margin_l = (side_margin + adjusted_padding as f64).round() as i32;
     let margin_r = (side_margin + adjusted_padding as f64).round() as i32;
-    
+
     // Calculate target position for \pos(x,y)
     // X: Center of screen + Offset (percentage of box width)
     let shift_x_px = box_width_px * (settings.text_offset_x as f64 / 100.0);
     let target_x = (play_res_x as f64 / 2.0) + shift_x_px;
-    
+
     // Y: Position% of screen + Offset (percentage of height)
     // We approximate height as 2 lines + padding for the offset calculation
     let approx_height = (adjusted_font_size as f64 * 2.0) + (adjusted_padding as f64 * 2.0);
     let shift_y_px = approx_height * (settings.text_offset_y as f64 / 100.0);
-    
+
     // Apply a vertical correction to raise the subtitles slightly
     // The font scaling (1.5x) pushes the bottom edge down, so we compensate by moving the center up
     // We use a factor of the font size as a heuristic for the correction
     let vertical_correction = (adjusted_font_size as f64) * 0.3;
-    
+
     let target_y = (play_res_y as f64 * (settings.position_percentage as f64 / 100.0)) + shift_y_px - vertical_correction;
-    
-    // Use Alignment 5 (Middle Center) to match Vue's translate(-50%, -50%)
-    let alignment = 5;
-    let margin_v = 10; // Not used for positioning with \pos, but required by Style
-    
-    let pos_tag = format!("{{\\pos({:.0},{:.0})}}", target_x, target_y);
 
+    // Calculate word spacing separator
+    // Frontend uses flex gap which replaces the space character.
+    // In ASS, we use a space character, so we need to adjust its spacing to match the desired gap.
+    // The space width is measured from the font (~0.25em when the font is unavailable).
+    // Target width = word_spacing * font_size
+    // Required spacing = Target width - Space width
+    let space_glyph_width = space_width(adjusted_font_size as f64, font) as f32;
+    let target_word_gap = settings.word_spacing * adjusted_font_size;
+    let space_char_spacing = (target_word_gap - space_glyph_width).max(0.0);
+
+    AssStyleLayout {
+        font_size_scale,
+        font_size: adjusted_font_size,
+        border1_width: adjusted_border1_width,
+        border2_width: adjusted_border2_width,
+        shadow: shadow_offset_magnitude,
+        letter_spacing: adjusted_letter_spacing,
+        padding: adjusted_padding,
+        margin_l,
+        margin_r,
+        margin_v: 10, // Not used for positioning with \pos, but required by Style
+        // Use Alignment 5 (Middle Center) to match Vue's translate(-50%, -50%)
+        alignment: 5,
+        // Standard ASS Bold flag (only for generic bold, specific weights handled via \fw)
+        bold: if settings.font_weight >= 700 { -1 } else { 0 },
+        italic: if settings.italic { -1 } else { 0 },
+        pos_x: target_x,
+        pos_y: target_y,
+        primary_color: to_ass_color(&settings.text_color),
+        border1_color: to_ass_color(&settings.border1_color),
+        border2_color: to_ass_color(&settings.border2_color),
+        // Use shadow_color for BackColour (which controls Shadow color in BorderStyle=1)
+        shadow_color: to_ass_color(&settings.shadow_color),
+        space_char_spacing,
+        // libass sizes fonts so one line (Windows ascent + descent) is exactly the font size;
+        // without the font's metrics assume the usual extra leading
+        line_height: if font.is_some() { adjusted_font_size } else { adjusted_font_size * 1.2 },
+        rtl: false,
+    }
+}
+
+// Write the two styles used for layered borders, named "{prefix}Border2Layer" and "{prefix}Border1Layer"
+// Layer ordering: shadow (bottom) > border2 (middle) > border1 (top) > text
+fn write_layer_styles(
+    file: &mut std::fs::File,
+    prefix: &str,
+    settings: &SubtitleSettings,
+    layout: &AssStyleLayout
+) {
     // For embedded fonts, we need to reference the actual font family name 
     // and use \fw tags for specific weights, as standard ASS only supports Bold/Italic.
     // This avoids issues where libass fails to match a constructed name like "Montserrat-Bold"
     // if the internal family name is just "Montserrat".
-    
-    // We'll use the base family name in the Style
-    let font_name_for_style = settings.font_family.clone();
-    
-    // But we still need to embed the specific font file corresponding to the weight.
-    // (This is handled by get_required_font_files and embed_fonts_in_ass)
-
-    // Standard ASS Bold flag (only for generic bold, specific weights handled via \fw)
-    let bold = if settings.font_weight >= 700 { -1 } else { 0 };
-    
-    println!("[Rust] Font name for ASS: {}", font_name_for_style);
+    let font_name_for_style = &settings.font_family;
 
-    // Generate two styles for layered borders
-    // Layer ordering: shadow (bottom) > border2 (middle) > border1 (top) > text
-    
     // Style 1: Border2Layer (bottom layer with larger outline = border1 + border2)
     // ASS Style format:
     // Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, 
     // Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, 
     // BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
-    let total_border_width = adjusted_border1_width + adjusted_border2_width;
-    
-    // Use shadow_color for BackColour (which controls Shadow color in BorderStyle=1)
-    let shadow_color_ass = convert_color(&settings.shadow_color);
-    
-    // Calculate word spacing separator
-    // Frontend uses flex gap which replaces the space character.
-    // In ASS, we use a space character, so we need to adjust its spacing to match the desired gap.
-    // We assume a standard space width of ~0.25em.
-    // Target width = word_spacing * font_size
-    // Required spacing = Target width - Estimated space width
-    let space_glyph_width = adjusted_font_size * 0.25;
-    let target_word_gap = settings.word_spacing * adjusted_font_size;
-    let space_char_spacing = (target_word_gap - space_glyph_width).max(0.0);
-    
-    // Separator: Set spacing for space char, then space char, then reset spacing for next word
-    let word_separator = format!("{{\\fsp{:.1}}} {{\\fsp{:.1}}}", space_char_spacing, adjusted_letter_spacing);
+    let total_border_width = layout.border1_width + layout.border2_width;
 
-    writeln!(file, "Style: Border2Layer,{},{},{},{},{},{},{},0,0,0,100,100,{},0,1,{},{},{},{},{},{},1",
+    writeln!(file, "Style: {}Border2Layer,{},{},{},{},{},{},{},{},0,0,100,100,{},0,1,{},{},{},{},{},{},{}",
+        prefix,
         font_name_for_style,
-        adjusted_font_size,
-        primary_color,
-        primary_color, // SecondaryColour
-        border2_color, // OutlineColour (border2 color)
-        shadow_color_ass, // BackColour (Shadow color)
-        bold,
-        adjusted_letter_spacing,
+        layout.font_size,
+        layout.primary_color,
+        layout.primary_color, // SecondaryColour
+        layout.border2_color, // OutlineColour (border2 color)
+        layout.shadow_color, // BackColour (Shadow color)
+        layout.bold,
+        layout.italic,
+        layout.letter_spacing,
         total_border_width, // Outline (total width)
-        adjusted_shadow, // Shadow (drop shadow)
-        alignment,
-        margin_l,
-        margin_r,
-        margin_v
+        layout.shadow, // Shadow (drop shadow)
+        layout.alignment,
+        layout.margin_l,
+        layout.margin_r,
+        layout.margin_v,
+        layout.encoding()
     ).unwrap();
 
-    // Style 2: Border1Layer (top layer with smaller outline = border1 only)
-    writeln!(file, "Style: Border1Layer,{},{},{},{},{},{},{},0,0,0,100,100,{},0,1,{},{},{},{},{},{},1",
+    // Style 2: Border1Layer (top layer with smaller outline = border1 only, no background)
+    writeln!(file, "Style: {}Border1Layer,{},{},{},{},{},&H00000000,{},{},0,0,100,100,{},0,1,{},{},{},{},{},{},{}",
+        prefix,
         font_name_for_style,
-        adjusted_font_size,
-        primary_color,
-        primary_color, // SecondaryColour
-        border1_color, // OutlineColour (border1 color)
-        "&H00000000".to_string(), // No background for top layer
-        bold,
-        adjusted_letter_spacing,
-        adjusted_border1_width, // Outline (border1 only)
+        layout.font_size,
+        layout.primary_color,
+        layout.primary_color, // SecondaryColour
+        layout.border1_color, // OutlineColour (border1 color)
+        layout.bold,
+        layout.italic,
+        layout.letter_spacing,
+        layout.border1_width, // Outline (border1 only)
         0.0, // No shadow on top layer
-        alignment,
-        margin_l,
-        margin_r,
-        margin_v
+        layout.alignment,
+        layout.margin_l,
+        layout.margin_r,
+        layout.margin_v,
+        layout.encoding()
     ).unwrap();
+}
 
-    writeln!(file, "").unwrap();
-    writeln!(file, "[Events]").unwrap();
-    writeln!(file, "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text").unwrap();
+// Style for the box behind the active word: an opaque box (BorderStyle 3) in the box color,
+// with the same font metrics as the text styles so the box lines up with the word
+fn write_highlight_box_style(
+    file: &mut std::fs::File,
+    prefix: &str,
+    settings: &SubtitleSettings,
+    layout: &AssStyleLayout
+) {
+    let box_color = to_ass_color(settings.active_box_color.as_deref().unwrap_or(&settings.background_color));
+    let box_padding = (layout.font_size * 0.12).max(2.0);
+    writeln!(file, "Style: {}HighlightBox,{},{},{},{},{},{},{},{},0,0,100,100,{},0,3,{},0,{},{},{},{},{}",
+        prefix,
+        settings.font_family,
+        layout.font_size,
+        box_color, // PrimaryColour (text is drawn by the layers above)
+        box_color, // SecondaryColour
+        box_color, // OutlineColour (box color for BorderStyle 3)
+        box_color, // BackColour
+        layout.bold,
+        layout.italic,
+        layout.letter_spacing,
+        box_padding, // Outline (box padding around the glyphs)
+        layout.alignment,
+        layout.margin_l,
+        layout.margin_r,
+        layout.margin_v,
+        layout.encoding()
+    ).unwrap();
+}
 
-    // 1. Flatten words relative to clip timeline
-    #[derive(Clone, Debug)]
-    struct ClipWord {
-        word: String,
-        start: f64,
-        end: f64,
+fn write_event(file: &mut std::fs::File, event: &AssEvent) {
+    writeln!(file, "Dialogue: {},{},{},{},,0,0,0,,{}",
+        event.layer,
+        format_ass_time(event.start),
+        format_ass_time(event.end),
+        event.style,
+        event.text
+    ).unwrap();
+}
+
+// Escape free text for an ASS Dialogue line (braces start override blocks, newlines become \N)
+pub fn escape_ass_text(text: &str) -> String {
+    text.replace('{', "\\{")
+        .replace('}', "\\}")
+        .replace("\r\n", "\\N")
+        .replace('\n', "\\N")
+}
+
+// Font families an ASS file renders with: the Fontname of every style and every \fn override
+pub fn ass_font_families(content: &str) -> Vec<String> {
+    let mut families: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if let Some(style) = line.strip_prefix("Style:") {
+            families.extend(style.split(',').nth(1).map(|name| name.trim().to_string()));
+        }
+        for (index, _) in line.match_indices("\\fn") {
+            let name = &line[index + 3..];
+            let end = name.find(['\\', '}']).unwrap_or(name.len());
+            families.push(name[..end].trim().to_string());
+        }
     }
+    families.retain(|family| !family.is_empty());
+    families.sort_by_key(|family| family.to_lowercase());
+    families.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    families
+}
 
-    let mut clip_timeline_words: Vec<ClipWord> = Vec::new();
-    let mut current_clip_time = 0.0;
-
-    for clip_segment in clip_segments {
-        let clip_seg_start = clip_segment["start_time"].as_f64().unwrap_or(0.0);
-        let clip_seg_end = clip_segment["end_time"].as_f64().unwrap_or(0.0);
-        let clip_seg_duration = clip_seg_end - clip_seg_start;
-
-        for word in all_words {
-            // Filter words within this segment
-            // Add buffer to catch boundary words
-            if word.start >= clip_seg_start - 0.1 && word.end <= clip_seg_end + 0.1 {
-                // Calculate relative timing and add the time offset (e.g., intro duration)
-                let start_rel = word.start - clip_seg_start + current_clip_time + time_offset;
-                let end_rel = word.end - clip_seg_start + current_clip_time + time_offset;
-                
-                clip_timeline_words.push(ClipWord {
-                    word: word.word.clone(),
-                    start: start_rel,
-                    end: end_rel,
-                });
-            }
+// Emoji code points (pictographs, symbols, flags, and the joiners/selectors inside emoji sequences)
+pub fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF | // Mahjong/cards, pictographs, emoticons, transport, supplemental symbols
+        0x2600..=0x27BF |   // Misc symbols and dingbats
+        0x2B00..=0x2BFF |   // Arrows, stars
+        0x2300..=0x23FF |   // Misc technical (watch, hourglass)
+        0xFE0F | 0x200D | 0x20E3 // Variation selector, zero-width joiner, keycap
+    )
+}
+
+// Switch to the emoji font for runs of emoji so they don't render as tofu in the text font
+pub fn wrap_emoji_runs(text: &str, font_family: &str) -> String {
+    let mut out = String::new();
+    let mut in_emoji = false;
+    for c in text.chars() {
+        let emoji = is_emoji(c);
+        if emoji != in_emoji {
+            let font = if emoji { EMOJI_FONT_FAMILY } else { font_family };
+            out.push_str(&format!("{{\\fn{}}}", font));
+            in_emoji = emoji;
         }
-        current_clip_time += clip_seg_duration;
+        out.push(c);
     }
+    if in_emoji {
+        out.push_str(&format!("{{\\fn{}}}", font_family));
+    }
+    out
+}
 
-    // Sort by start time just in case
-    clip_timeline_words.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap_or(std::cmp::Ordering::Equal));
+// Generate the text overlay track (hook headlines, end-card CTAs) as its own ASS file.
+// Each item gets its own style pair ("Title{n}Border2Layer"/"Title{n}Border1Layer") and renders on
+// layers 10/11, so it never interferes with the transcript caption styles or layers.
+pub fn generate_text_overlay_ass_file(
+    items: &[TextOverlayItem],
+    output_path: &std::path::Path,
+    aspect_ratio: Option<&AspectRatio>,
+    video_width: u32,
+    video_height: u32,
+    fonts_dir: Option<&std::path::Path>
+) -> Result<(), String> {
+    let mut file = std::fs::File::create(output_path)
+        .map_err(|e| format!("Failed to create text overlay file: {}", e))?;
 
-    if clip_timeline_words.is_empty() {
-        return Ok(());
+    // Same normalized 1080p coordinate system as the captions
+    let play_res_y = 1080;
+    let play_res_x = (video_width as f64 * (1080.0 / video_height as f64)).round() as u32;
+
+    write_script_info(&mut file, play_res_x, play_res_y);
+
+    // Embed each distinct font once (the emoji font is picked up from fontsdir instead - it is large)
+    let mut font_files: Vec<std::path::PathBuf> = Vec::new();
+    for item in items {
+        for font_file in required_font_files(&item.style, fonts_dir) {
+            if !font_files.contains(&font_file) {
+                font_files.push(font_file);
+            }
+        }
+    }
+    embed_font_files_in_ass(&mut file, font_files)?;
+
+    writeln!(file).unwrap();
+    write_styles_header(&mut file);
+
+    let layouts: Vec<AssStyleLayout> = items
+        .iter()
+        .map(|item| {
+            let font_metrics = load_font_metrics(&item.style, fonts_dir);
+            let mut layout = compute_style_layout(&item.style, aspect_ratio, play_res_x, play_res_y, font_metrics.as_ref());
+            layout.rtl = is_rtl_text(item.text.split_whitespace());
+            layout
+        })
+        .collect();
+    for (i, (item, layout)) in items.iter().zip(&layouts).enumerate() {
+        write_layer_styles(&mut file, &format!("Title{}", i + 1), &item.style, layout);
     }
 
-    // 2. Group words into chunks (pages)
-    let _chunks: Vec<&[ClipWord]> = clip_timeline_words.chunks(max_words).collect();
+    write_events_header(&mut file);
 
-    // 3. Generate events for each chunk
-    let chunk_count = (clip_timeline_words.len() + max_words - 1) / max_words;
-    
-    for i in 0..chunk_count {
-        let start_idx = i * max_words;
-        let end_idx = std::cmp::min(start_idx + max_words, clip_timeline_words.len());
-        let chunk = &clip_timeline_words[start_idx..end_idx];
-        
-        if chunk.is_empty() { continue; }
+    for (i, (item, layout)) in items.iter().zip(&layouts).enumerate() {
+        if item.end - item.start < 0.01 || item.text.trim().is_empty() {
+            continue;
+        }
 
-        let chunk_visible_start = if i == 0 {
-            time_offset  // Start at the time offset (after intro)
+        let fade_tag = if item.fade_in_ms > 0 || item.fade_out_ms > 0 {
+            format!("{{\\fad({},{})}}", item.fade_in_ms, item.fade_out_ms)
         } else {
-            clip_timeline_words[start_idx - 1].end
+            String::new()
         };
-        
-        let chunk_visible_end = chunk.last().unwrap().end;
-        
-        // Now we need to generate events for each "state" within this chunk visibility.
-        // States are defined by the word boundaries within the chunk.
-        // Transitions happen at: word.start, word.end.
-        
-        // We have a timeline of points: chunk_visible_start, w0.start, w0.end, w1.start, w1.end... chunk_visible_end.
-        // Sort and deduplicate these points.
-        let mut points = Vec::new();
-        points.push(chunk_visible_start);
-        for word in chunk {
-            points.push(word.start);
-            points.push(word.end);
-        }
-        points.push(chunk_visible_end);
-        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        points.dedup();
-        
-        // Iterate intervals
-        for j in 0..points.len()-1 {
-            let t_start = points[j];
-            let t_end = points[j+1];
-            
-            if t_end - t_start < 0.01 { continue; } // Skip tiny intervals
-            
-            // Determine active word in this interval
-            // A word is active if t_mid is inside [word.start, word.end]
-            let t_mid = (t_start + t_end) / 2.0;
-            let active_word_idx = chunk.iter().position(|w| t_mid >= w.start && t_mid <= w.end);
-            
-            // Format time to H:MM:SS.cc
-            let format_time = |t: f64| -> String {
-                let t = t.max(0.0);
-                let hours = (t / 3600.0).floor() as u32;
-                let mins = ((t % 3600.0) / 60.0).floor() as u32;
-                let secs = (t % 60.0).floor() as u32;
-                let centis = ((t % 1.0) * 100.0).round() as u32;
-                format!("{}:{:02}:{:02}.{:02}", hours, mins, secs, centis)
-            };
-            
-            // Strategy: Render text in four layers for dual borders + animation
-            // Layer 0: Border2Layer base text (shadow + outer border)
-            // Layer 1: Border2Layer active word animation (shadow + outer border, scaled)
-            // Layer 2: Border1Layer base text (inner border)
-            // Layer 3: Border1Layer active word animation (inner border, scaled)
-            
-            // Layer 0: Border2Layer base text with all words at normal size
-            // Use \fw tag to ensure correct font weight
-            let weight_tag = format!("{{\\fw{}}}", settings.font_weight);
-            let base_text = chunk.iter().map(|w| format!("{}{}", weight_tag, w.word)).collect::<Vec<_>>().join(&word_separator);
-            
-            writeln!(file, "Dialogue: 0,{},{},Border2Layer,,0,0,0,,{}{}",
-                format_time(t_start),
-                format_time(t_end),
-                pos_tag,
-                base_text
-            ).unwrap();
-            
-            // Layer 2: Border1Layer base text with all words at normal size
-            writeln!(file, "Dialogue: 2,{},{},Border1Layer,,0,0,0,,{}{}",
-                format_time(t_start),
-                format_time(t_end),
-                pos_tag,
-                base_text
+        let weight_tag = format!("{{\\fw{}}}", item.style.font_weight);
+        let text = wrap_emoji_runs(&escape_ass_text(item.text.trim()), &item.style.font_family);
+
+        for (layer, style) in [(10, "Border2Layer"), (11, "Border1Layer")] {
+            writeln!(file, "Dialogue: {},{},{},Title{}{},,0,0,0,,{}{}{}{}",
+                layer,
+                format_ass_time(item.start),
+                format_ass_time(item.end),
+                i + 1,
+                style,
+                layout.pos_tag(),
+                fade_tag,
+                weight_tag,
+                text
             ).unwrap();
-            
-            // Layers 1 & 3: If there's an active word, render it scaled on top (for both border layers)
-            if let Some(active_idx) = active_word_idx {
-                let active_word = &chunk[active_idx];
-                let word_duration = active_word.end - active_word.start;
-                let anim_duration_ms = calculate_animation_duration(word_duration);
-                
-                // Calculate when this word starts within the current interval
-                let word_start_in_interval = if active_word.start > t_start {
-                    ((active_word.start - t_start) * 1000.0) as u32
-                } else {
-                    0
-                };
-                
-                let scale_up_end = word_start_in_interval + anim_duration_ms;
-                
-                // Build text with spaces to position the active word correctly
-                // Use invisible characters (zero-width) for other words to maintain spacing
-                let mut positioned_text_parts = Vec::new();
-                for (k, word) in chunk.iter().enumerate() {
-                    if k == active_idx {
-                        // Active word with animation
-                        positioned_text_parts.push(format!(
-                            "{}{{\\r\\t({},{},\\fscx115\\fscy115)}}{}{{\\fscx100\\fscy100}}",
-                            weight_tag,
-                            word_start_in_interval,
-                            scale_up_end,
-                            word.word
-                        ));
-                    } else {
-                        // Use {\alpha&HFF&} to make word invisible (maintains spacing)
-                        // Still include weight tag to maintain spacing metrics
-                        positioned_text_parts.push(format!("{}{{\\alpha&HFF&}}{}", weight_tag, word.word));
-                    }
-                }
-                
-                let overlay_text = positioned_text_parts.join(&word_separator);
-                
-                // Layer 1: Border2Layer active word (shadow + outer border)
-                writeln!(file, "Dialogue: 1,{},{},Border2Layer,,0,0,0,,{}{}",
-                    format_time(t_start),
-                    format_time(t_end),
-                    pos_tag,
-                    overlay_text
-                ).unwrap();
-                
-                // Layer 3: Border1Layer active word (inner border)
-                writeln!(file, "Dialogue: 3,{},{},Border1Layer,,0,0,0,,{}{}",
-                    format_time(t_start),
-                    format_time(t_end),
-                    pos_tag,
-                    overlay_text
-                ).unwrap();
-            }
         }
     }
 
+    println!("[Rust] Wrote {} text overlay(s) to {}", items.len(), output_path.display());
     Ok(())
 }
 
-// Calculate animation duration for a word (matches VideoPlayer.vue logic)
-pub fn calculate_animation_duration(word_duration: f64) -> u32 {
-    // Returns duration in milliseconds
-    
-    // For very short words (under 50ms), use instant transition
-    if word_duration < 0.05 {
-        return 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_emoji_runs() {
+        assert_eq!(
+            wrap_emoji_runs("He did it 😱🔥!", "Montserrat"),
+            "He did it {\\fnNoto Emoji}😱🔥{\\fnMontserrat}!"
+        );
+        assert_eq!(wrap_emoji_runs("plain", "Montserrat"), "plain");
     }
-    
-    // For short words (50-100ms), use 30% of duration for responsive animation
-    if word_duration < 0.1 {
-        return ((word_duration * 0.3) * 1000.0) as u32;
-    }
-    
-    // For medium words (100-200ms), use 35% of duration
-    if word_duration < 0.2 {
-        return ((word_duration * 0.35) * 1000.0) as u32;
+
+    #[test]
+    fn test_ass_font_families() {
+        let content = "[V4+ Styles]\n\
+            Style: Default,Montserrat,64,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,-1,0\n\
+            Style: Speaker1,montserrat,64,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,-1,0\n\
+            [Events]\n\
+            Dialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Wow {\\fnNoto Emoji\\fs80}😱{\\fnBebas Neue}!\n";
+        assert_eq!(ass_font_families(content), vec!["Bebas Neue", "Montserrat", "Noto Emoji"]);
     }
-    
-    // For normal words (200-400ms), use 40% of duration
-    if word_duration < 0.4 {
-        return ((word_duration * 0.4) * 1000.0) as u32;
+
+    #[test]
+    fn test_escape_ass_text() {
+        assert_eq!(escape_ass_text("Link {below}\nNow"), "Link \\{below\\}\\NNow");
     }
-    
-    // For longer words (400ms+), use 45% but cap at 200ms to prevent overly slow animations
-    let calculated_duration = word_duration * 0.45;
-    let capped_duration = calculated_duration.min(0.2);
-    (capped_duration * 1000.0) as u32
 }
-
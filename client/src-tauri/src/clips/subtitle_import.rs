@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+
+use super::captions::interpolate_words;
+use super::script::split_caption_tokens;
+use super::types::{WhisperSegment, WordInfo};
+
+// Imports existing caption files (SRT, WebVTT, ASS/SSA) as a transcript: one segment per cue,
+// with word times taken from WebVTT timestamp tags or ASS karaoke tags where present, and
+// interpolated by word length otherwise.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedSubtitles {
+    // "srt", "vtt" or "ass"
+    pub format: String,
+    pub segments: Vec<WhisperSegment>,
+    pub words: Vec<WordInfo>,
+}
+
+// A parsed cue: text runs, each with the time it starts being spoken when the file says so
+struct Cue {
+    start: f64,
+    end: f64,
+    speaker: Option<String>,
+    runs: Vec<(Option<f64>, String)>,
+}
+
+// Parse "HH:MM:SS,mmm", "MM:SS.mmm" or ASS "H:MM:SS.cc" into seconds
+fn parse_timestamp(text: &str) -> Option<f64> {
+    let text = text.trim().replace(',', ".");
+    let parts: Vec<&str> = text.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (h.parse::<f64>().ok()?, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        [m, s] => (0.0, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        _ => return None,
+    };
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+// "start --> end [cue settings]"
+fn parse_timing_line(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start)?, parse_timestamp(end)?))
+}
+
+fn unescape_markup(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&nbsp;", " ").replace("&amp;", "&")
+}
+
+// Remove <tags> (and {\ass} blocks some SRT files carry)
+fn strip_markup(text: &str) -> String {
+    let mut out = String::new();
+    let mut closing: Option<char> = None;
+    for c in text.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (Some(close), c) if c == close => closing = None,
+            (None, c) => out.push(c),
+            _ => {}
+        }
+    }
+    unescape_markup(&out)
+}
+
+// Blocks of non-empty lines
+fn blocks(content: &str) -> Vec<Vec<&str>> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+fn parse_srt(content: &str) -> Vec<Cue> {
+    blocks(content)
+        .into_iter()
+        .filter_map(|block| {
+            let timing_idx = block.iter().position(|line| line.contains("-->"))?;
+            let (start, end) = parse_timing_line(block[timing_idx])?;
+            let text: Vec<String> = block[timing_idx + 1..].iter().map(|line| strip_markup(line)).collect();
+            Some(Cue { start, end, speaker: None, runs: vec![(None, text.join(" "))] })
+        })
+        .collect()
+}
+
+// WebVTT cue payload: split at <timestamp> tags, speaker from the first <v Name> tag
+fn parse_vtt_payload(payload: &str) -> (Option<String>, Vec<(Option<f64>, String)>) {
+    let mut speaker = None;
+    let mut runs: Vec<(Option<f64>, String)> = vec![(None, String::new())];
+    let mut rest = payload;
+    while let Some(open) = rest.find('<') {
+        runs.last_mut().unwrap().1.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            rest = &rest[open + 1..];
+            continue;
+        };
+        let tag = &rest[open + 1..open + close];
+        if let Some(time) = parse_timestamp(tag).filter(|_| tag.contains(':')) {
+            runs.push((Some(time), String::new()));
+        } else if let Some(name) = tag.strip_prefix("v ").or_else(|| tag.strip_prefix("v.")) {
+            let name = name.split_once(' ').map(|(_, n)| n).unwrap_or(name);
+            speaker = speaker.or_else(|| Some(name.trim().to_string()).filter(|n| !n.is_empty()));
+        }
+        rest = &rest[open + close + 1..];
+    }
+    runs.last_mut().unwrap().1.push_str(rest);
+    let runs = runs.into_iter().map(|(time, text)| (time, unescape_markup(&text))).collect();
+    (speaker, runs)
+}
+
+fn parse_vtt(content: &str) -> Vec<Cue> {
+    blocks(content)
+        .into_iter()
+        .filter_map(|block| {
+            // Header, NOTE, STYLE and REGION blocks have no timing line
+            let timing_idx = block.iter().position(|line| line.contains("-->"))?;
+            let (start, end) = parse_timing_line(block[timing_idx])?;
+            let (speaker, runs) = parse_vtt_payload(&block[timing_idx + 1..].join(" "));
+            Some(Cue { start, end, speaker, runs })
+        })
+        .collect()
+}
+
+// ASS dialogue text: drop override blocks and drawings, turn karaoke tags into timed runs
+fn parse_ass_text(text: &str, start: f64) -> Vec<(Option<f64>, String)> {
+    let mut runs: Vec<(Option<f64>, String)> = vec![(None, String::new())];
+    let mut karaoke_time = start;
+    let mut drawing = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some(block) = rest.strip_prefix('{') {
+            let close = block.find('}').unwrap_or(block.len());
+            for tag in block[..close].split('\\').map(str::trim) {
+                let karaoke = ["kf", "ko", "K", "k"].iter().find_map(|name| tag.strip_prefix(name)?.parse::<f64>().ok());
+                if let Some(centiseconds) = karaoke {
+                    runs.push((Some(karaoke_time), String::new()));
+                    karaoke_time += centiseconds / 100.0;
+                } else if let Some(level) = tag.strip_prefix('p').and_then(|l| l.parse::<u32>().ok()) {
+                    drawing = level > 0;
+                }
+            }
+            rest = block.get(close + 1..).unwrap_or("");
+            continue;
+        }
+        let next = rest.find('{').unwrap_or(rest.len());
+        if !drawing {
+            let plain = rest[..next].replace("\\N", " ").replace("\\n", " ").replace("\\h", " ");
+            runs.last_mut().unwrap().1.push_str(&plain);
+        }
+        rest = &rest[next..];
+    }
+    runs
+}
+
+fn parse_ass(content: &str) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut seen: Vec<(i64, i64, String)> = Vec::new();
+    let mut in_events = false;
+    let mut format: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields.split(',').map(|f| f.trim().to_lowercase()).collect();
+            continue;
+        }
+        let Some(values) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+        if format.is_empty() {
+            continue;
+        }
+        // The text field is last and may contain commas
+        let values: Vec<&str> = values.splitn(format.len(), ',').map(str::trim).collect();
+        let field = |name: &str| format.iter().position(|f| f == name).and_then(|i| values.get(i).copied());
+        let (Some(start), Some(end), Some(text)) = (
+            field("start").and_then(parse_timestamp),
+            field("end").and_then(parse_timestamp),
+            field("text"),
+        ) else {
+            continue;
+        };
+
+        let runs = parse_ass_text(text, start);
+        let plain: String = runs.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>().join("");
+        let plain = plain.split_whitespace().collect::<Vec<_>>().join(" ");
+        // Skip drawings (background boxes) and the same line repeated on several layers
+        let key = ((start * 100.0).round() as i64, (end * 100.0).round() as i64, plain.clone());
+        if plain.is_empty() || seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+        let speaker = field("name").map(|n| n.to_string()).filter(|n| !n.is_empty());
+        cues.push(Cue { start, end, speaker, runs });
+    }
+    cues.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap_or(std::cmp::Ordering::Equal));
+    cues
+}
+
+// Words of a cue: each timed run spans until the next run (or the cue end)
+fn cue_words(cue: &Cue) -> Vec<WordInfo> {
+    let mut words = Vec::new();
+    for (i, (time, text)) in cue.runs.iter().enumerate() {
+        let start = time.unwrap_or(cue.start).clamp(cue.start, cue.end);
+        let end = cue.runs[i + 1..]
+            .iter()
+            .find_map(|(time, _)| *time)
+            .unwrap_or(cue.end)
+            .clamp(start, cue.end);
+        words.extend(interpolate_words(split_caption_tokens(text), start, end, cue.speaker.as_deref()));
+    }
+    words
+}
+
+// Format from the file extension, or from the content when the extension is unknown
+pub fn detect_subtitle_format(path: &std::path::Path, content: &str) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("srt") => "srt",
+        Some("vtt") => "vtt",
+        Some("ass") | Some("ssa") => "ass",
+        _ if content.trim_start_matches('\u{feff}').starts_with("WEBVTT") => "vtt",
+        _ if content.contains("[Events]") => "ass",
+        _ => "srt",
+    }
+}
+
+// Parse caption file content into transcript segments and words
+pub fn parse_subtitles(content: &str, format: &str) -> Result<ImportedSubtitles, String> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let cues = match format {
+        "srt" => parse_srt(&content),
+        "vtt" => parse_vtt(&content),
+        "ass" => parse_ass(&content),
+        other => return Err(format!("Unsupported subtitle format: {}", other)),
+    };
+    if cues.is_empty() {
+        return Err(format!("No subtitle cues found in the {} file", format));
+    }
+
+    let segments: Vec<WhisperSegment> = cues
+        .iter()
+        .enumerate()
+        .filter_map(|(i, cue)| {
+            let words = cue_words(cue);
+            if words.is_empty() {
+                return None;
+            }
+            Some(WhisperSegment {
+                id: i as i64,
+                start: cue.start,
+                end: cue.end,
+                text: words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>().join(" "),
+                words: Some(words),
+                speaker: cue.speaker.clone(),
+            })
+        })
+        .collect();
+    let words = segments.iter().flat_map(|s| s.words.iter().flatten().cloned()).collect();
+
+    Ok(ImportedSubtitles { format: format.to_string(), segments, words })
+}
+
+// Read and parse a caption file
+pub fn import_subtitle_file(path: &std::path::Path) -> Result<ImportedSubtitles, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read subtitle file: {}", e))?;
+    let format = detect_subtitle_format(path, &content);
+    let imported = parse_subtitles(&content, format)?;
+    println!("[Rust] Imported {} cues ({} words) from {} file", imported.segments.len(), imported.words.len(), format);
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_srt_interpolates_word_times() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,000\n<i>Hi</i> there\n\n2\n00:00:03,500 --> 00:00:04,000\nBye\n";
+        let imported = parse_subtitles(srt, "srt").unwrap();
+        assert_eq!(imported.segments.len(), 2);
+        let words: Vec<&str> = imported.words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["Hi", "there", "Bye"]);
+        // "Hi" has 2 of the cue's 7 characters
+        assert!(close(imported.words[0].end, 1.0 + 2.0 / 7.0));
+        assert!(close(imported.words[1].end, 2.0));
+    }
+
+    #[test]
+    fn test_vtt_word_timestamps_and_voice() {
+        let vtt = "WEBVTT\n\nNOTE exported\n\n00:01.000 --> 00:03.000\n<v Alex><c>one</c> <00:00:02.000><c>two</c> three\n";
+        let imported = parse_subtitles(vtt, "vtt").unwrap();
+        let words = &imported.words;
+        assert_eq!(words.len(), 3);
+        assert!(close(words[0].start, 1.0) && close(words[0].end, 2.0));
+        assert!(close(words[1].start, 2.0));
+        assert!(close(words[2].end, 3.0));
+        assert_eq!(words[0].speaker.as_deref(), Some("Alex"));
+    }
+
+    #[test]
+    fn test_ass_karaoke_and_layers() {
+        let ass = "[Script Info]\nTitle: x\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,Sam,0,0,0,,{\\k50}Hello,{\\k50}\\Nworld\n\
+Dialogue: 1,0:00:01.00,0:00:02.00,Default,Sam,0,0,0,,{\\k50}Hello,{\\k50}\\Nworld\n\
+Dialogue: 0,0:00:01.00,0:00:02.00,Box,,0,0,0,,{\\p1}m 0 0 l 10 0 10 10{\\p0}\n";
+        let imported = parse_subtitles(ass, "ass").unwrap();
+        assert_eq!(imported.segments.len(), 1);
+        assert_eq!(imported.segments[0].text, "Hello, world");
+        assert!(close(imported.words[1].start, 1.5));
+        assert_eq!(imported.words[1].speaker.as_deref(), Some("Sam"));
+    }
+}
@@ -0,0 +1,313 @@
+// Text layout for captions in ASS files: measures words with the bundled fonts (falling back to
+// approximate widths when a font is unavailable), breaks pages into balanced lines and places
+// per-word decorations (boxes behind words). Lines are centered on \\pos (alignment 5);
+// right-to-left pages run from the right edge of each line.
+
+use ttf_parser::{Face, GlyphId};
+
+use super::script::needs_space_between;
+
+// Horizontal extent of one word on the ASS canvas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WordBox {
+    pub left: f64,
+    pub right: f64,
+    pub center_y: f64,
+}
+
+// Inputs for laying out a page of words
+pub struct LineLayoutParams<'a> {
+    pub font_size: f64,
+    pub letter_spacing: f64,
+    // Extra spacing applied to the space glyph between words
+    pub space_spacing: f64,
+    // Width available for a line before libass wraps
+    pub max_line_width: f64,
+    pub center_x: f64,
+    pub center_y: f64,
+    pub line_height: f64,
+    // Metrics of the font libass will render with (approximate widths if None)
+    pub font: Option<&'a FontMetrics>,
+    // Words run right to left within each line
+    pub rtl: bool,
+}
+
+// Glyph metrics of a TTF/OTF font, scaled the way libass scales fonts: an ASS font size is the
+// height of the Windows ascent + descent, not the em square
+pub struct FontMetrics {
+    data: Vec<u8>,
+    // Font units per ASS font size
+    units_per_size: f64,
+}
+
+impl FontMetrics {
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let data = std::fs::read(path)
+            .map_err(|e| format!("Failed to read font file {}: {}", path.display(), e))?;
+        let units_per_size = {
+            let face = Face::parse(&data, 0)
+                .map_err(|e| format!("Failed to parse font file {}: {}", path.display(), e))?;
+            let win_height = face.tables().os2
+                .map(|os2| os2.windows_ascender() as f64 - os2.windows_descender() as f64)
+                .unwrap_or(0.0);
+            if win_height > 0.0 {
+                win_height
+            } else {
+                (face.ascender() as f64 - face.descender() as f64).max(face.units_per_em() as f64)
+            }
+        };
+        Ok(Self { data, units_per_size })
+    }
+
+    fn face(&self) -> Option<Face<'_>> {
+        Face::parse(&self.data, 0).ok()
+    }
+
+    // Advance of each character (in font sizes), including kerning with the previous character.
+    // None for characters the font has no glyph for (libass falls back to another font for those).
+    fn advances(&self, text: &str) -> Vec<Option<f64>> {
+        let Some(face) = self.face() else {
+            return text.chars().map(|_| None).collect();
+        };
+        let mut previous: Option<GlyphId> = None;
+        text.chars()
+            .map(|c| {
+                let glyph = face.glyph_index(c);
+                let advance = glyph.and_then(|g| face.glyph_hor_advance(g)).map(|a| {
+                    let kerning = match (previous, glyph) {
+                        (Some(left), Some(right)) => glyph_kerning(&face, left, right),
+                        _ => 0.0,
+                    };
+                    (a as f64 + kerning) / self.units_per_size
+                });
+                previous = glyph;
+                advance
+            })
+            .collect()
+    }
+}
+
+// Horizontal kerning between two glyphs from the legacy kern table
+fn glyph_kerning(face: &Face, left: GlyphId, right: GlyphId) -> f64 {
+    face.tables().kern
+        .map(|kern| {
+            kern.subtables
+                .into_iter()
+                .filter(|subtable| subtable.horizontal && !subtable.variable)
+                .filter_map(|subtable| subtable.glyphs_kerning(left, right))
+                .map(|value| value as f64)
+                .sum()
+        })
+        .unwrap_or(0.0)
+}
+
+// Approximate advance of a character as a fraction of the font size
+fn char_advance_em(c: char) -> f64 {
+    match c {
+        'i' | 'j' | 'l' | '!' | '\'' | '.' | ',' | ':' | ';' | '|' | 'I' => 0.28,
+        'f' | 't' | 'r' | '(' | ')' | '-' => 0.38,
+        'm' | 'w' | 'W' => 0.85,
+        'M' => 0.9,
+        ' ' => 0.25,
+        c if c.is_ascii_digit() => 0.58,
+        c if c.is_ascii_uppercase() => 0.68,
+        c if c.is_ascii() => 0.55,
+        // Emoji and CJK are roughly square
+        c if super::subtitle::is_emoji(c) || (c as u32) >= 0x2E80 => 1.0,
+        _ => 0.6,
+    }
+}
+
+// Rendered width of a word (letter spacing is added after every character, like \\fsp)
+pub fn measure_word(word: &str, font_size: f64, letter_spacing: f64, font: Option<&FontMetrics>) -> f64 {
+    let advances = match font {
+        Some(font) => font.advances(word),
+        None => word.chars().map(|_| None).collect(),
+    };
+    word.chars()
+        .zip(advances)
+        .map(|(c, advance)| advance.unwrap_or_else(|| char_advance_em(c)) * font_size + letter_spacing)
+        .sum()
+}
+
+// Width of the space glyph at the given font size
+pub fn space_width(font_size: f64, font: Option<&FontMetrics>) -> f64 {
+    measure_word(" ", font_size, 0.0, font)
+}
+
+// Width of the separator between two words (space glyph plus its extra spacing)
+fn separator_width(params: &LineLayoutParams) -> f64 {
+    space_width(params.font_size, params.font) + params.space_spacing
+}
+
+// Width of the words [start, end) set on one line. gaps[i] is the space before word i
+// (no space between Chinese/Japanese words).
+fn line_width(widths: &[f64], gaps: &[f64], start: usize, end: usize) -> f64 {
+    widths[start..end].iter().sum::<f64>() + gaps[(start + 1).min(end)..end].iter().sum::<f64>()
+}
+
+// Greedy end-of-line wrapping: the number of lines libass would need
+fn greedy_line_count(widths: &[f64], gaps: &[f64], max_line_width: f64) -> usize {
+    let mut lines = 0;
+    let mut current_width: Option<f64> = None;
+    for (width, gap) in widths.iter().zip(gaps) {
+        current_width = match current_width {
+            Some(current) if current + gap + width <= max_line_width => Some(current + gap + width),
+            _ => {
+                lines += 1;
+                Some(*width)
+            }
+        };
+    }
+    lines
+}
+
+// Split words into line_count lines so the widest line is as narrow as possible
+// (balanced captions instead of a full first line and a single orphaned word)
+fn balance_lines(widths: &[f64], gaps: &[f64], line_count: usize) -> Vec<std::ops::Range<usize>> {
+    let n = widths.len();
+    let line_count = line_count.clamp(1, n.max(1));
+    // best[k][j]: narrowest widest-line for the first j words on k lines, with the split that achieves it
+    let mut best = vec![vec![(f64::INFINITY, 0usize); n + 1]; line_count + 1];
+    best[0][0] = (0.0, 0);
+    for k in 1..=line_count {
+        for j in k..=n {
+            for i in (k - 1)..j {
+                let widest = best[k - 1][i].0.max(line_width(widths, gaps, i, j));
+                if widest < best[k][j].0 {
+                    best[k][j] = (widest, i);
+                }
+            }
+        }
+    }
+
+    let mut lines = Vec::with_capacity(line_count);
+    let mut end = n;
+    for k in (1..=line_count).rev() {
+        let start = best[k][end].1;
+        lines.push(start..end);
+        end = start;
+    }
+    lines.reverse();
+    lines
+}
+
+// Lay out words into balanced, centered lines and return each word's box
+pub fn layout_words(words: &[&str], params: &LineLayoutParams) -> Vec<WordBox> {
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let widths: Vec<f64> = words
+        .iter()
+        .map(|w| measure_word(w, params.font_size, params.letter_spacing, params.font))
+        .collect();
+    let separator = separator_width(params);
+    let gaps: Vec<f64> = (0..words.len())
+        .map(|i| if i > 0 && needs_space_between(words[i - 1], words[i]) { separator } else { 0.0 })
+        .collect();
+    let lines = balance_lines(&widths, &gaps, greedy_line_count(&widths, &gaps, params.max_line_width));
+
+    let mut boxes = vec![WordBox { left: 0.0, right: 0.0, center_y: 0.0 }; words.len()];
+    let first_line_y = params.center_y - (lines.len() as f64 - 1.0) / 2.0 * params.line_height;
+    for (line_idx, line) in lines.iter().enumerate() {
+        let width = line_width(&widths, &gaps, line.start, line.end);
+        let line_left = params.center_x - width / 2.0;
+        let y = first_line_y + line_idx as f64 * params.line_height;
+        let mut x = line_left;
+        for i in line.clone() {
+            x += if i > line.start { gaps[i] } else { 0.0 };
+            // Right-to-left lines are mirrored: the first word sits at the right edge
+            let left = if params.rtl { 2.0 * line_left + width - x - widths[i] } else { x };
+            boxes[i] = WordBox { left, right: left + widths[i], center_y: y };
+            x += widths[i];
+        }
+    }
+    boxes
+}
+
+// Indices of the words that start a new line (every line but the first)
+pub fn line_starts(boxes: &[WordBox]) -> Vec<usize> {
+    (1..boxes.len())
+        .filter(|&i| boxes[i].center_y > boxes[i - 1].center_y + 0.5)
+        .collect()
+}
+
+// ASS drawing (for \p1) of a rounded rectangle with its top-left corner at the origin
+pub fn rounded_rect_drawing(width: f64, height: f64, radius: f64) -> String {
+    let r = radius.clamp(0.0, width.min(height) / 2.0);
+    if r < 0.5 {
+        return format!("m 0 0 l {w:.0} 0 l {w:.0} {h:.0} l 0 {h:.0}", w = width, h = height);
+    }
+    // Corners as cubic beziers with both control points on the corner
+    format!(
+        "m {r:.0} 0 l {wr:.0} 0 b {w:.0} 0 {w:.0} 0 {w:.0} {r:.0} l {w:.0} {hr:.0} b {w:.0} {h:.0} {w:.0} {h:.0} {wr:.0} {h:.0} \
+         l {r:.0} {h:.0} b 0 {h:.0} 0 {h:.0} 0 {hr:.0} l 0 {r:.0} b 0 0 0 0 {r:.0} 0",
+        r = r, w = width, h = height, wr = width - r, hr = height - r
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(max_line_width: f64) -> LineLayoutParams<'static> {
+        LineLayoutParams {
+            font_size: 100.0,
+            letter_spacing: 0.0,
+            space_spacing: 0.0,
+            max_line_width,
+            center_x: 500.0,
+            center_y: 500.0,
+            line_height: 120.0,
+            font: None,
+            rtl: false,
+        }
+    }
+
+    #[test]
+    fn test_single_line_is_centered() {
+        let boxes = layout_words(&["ab", "ab"], &params(1000.0));
+        // Each word 110 wide, separator 25: line 245 wide starting at 377.5
+        assert!((boxes[0].left - 377.5).abs() < 1e-9);
+        assert!((boxes[1].right - 622.5).abs() < 1e-9);
+        assert_eq!(boxes[0].center_y, 500.0);
+    }
+
+    #[test]
+    fn test_wrapping_stacks_lines_around_center() {
+        let boxes = layout_words(&["abcd", "abcd"], &params(300.0));
+        assert_eq!(boxes[0].center_y, 440.0);
+        assert_eq!(boxes[1].center_y, 560.0);
+        assert_eq!(line_starts(&boxes), vec![1]);
+    }
+
+    #[test]
+    fn test_lines_are_balanced() {
+        // Greedy wrapping would give "abcd abcd" / "abcd"; balanced gives 1 + 2 words
+        let boxes = layout_words(&["abcd", "abcd", "abcd"], &params(500.0));
+        assert_eq!(line_starts(&boxes), vec![1]);
+        let boxes = layout_words(&["ab", "abcd", "abcd", "ab"], &params(500.0));
+        assert_eq!(line_starts(&boxes), vec![2]);
+    }
+
+    #[test]
+    fn test_rtl_and_unspaced_words() {
+        let rtl = LineLayoutParams { rtl: true, ..params(1000.0) };
+        let boxes = layout_words(&["ab", "ab"], &rtl);
+        assert!((boxes[0].right - 622.5).abs() < 1e-9);
+        assert!((boxes[1].left - 377.5).abs() < 1e-9);
+        // No gap between Chinese characters
+        let boxes = layout_words(&["我", "们"], &params(1000.0));
+        assert!((boxes[1].left - boxes[0].right).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bundled_font_measurement() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/Montserrat-Regular.ttf");
+        let font = FontMetrics::load(&path).unwrap();
+        let wide = measure_word("WWW", 100.0, 0.0, Some(&font));
+        let narrow = measure_word("iii", 100.0, 0.0, Some(&font));
+        assert!(wide > narrow * 2.0);
+        assert!(space_width(100.0, Some(&font)) > 10.0 && space_width(100.0, Some(&font)) < 50.0);
+    }
+}
@@ -8,6 +8,8 @@ pub struct SubtitleSettings {
     pub font_family: String,
     pub font_size: f32,
     pub font_weight: u32,
+    #[serde(default)]
+    pub italic: bool,
     pub text_color: String,
     pub background_color: String,
     pub background_enabled: bool,
@@ -31,6 +33,129 @@ pub struct SubtitleSettings {
     pub padding: f32,
     pub border_radius: f32,
     pub word_spacing: f32,
+    // Active-word emphasis (missing from older presets, so everything has a default)
+    #[serde(default)]
+    pub active_word_color: Option<String>,
+    #[serde(default)]
+    pub active_box_color: Option<String>,
+    #[serde(default = "default_active_box_padding")]
+    pub active_box_padding: f32,
+    #[serde(default = "default_active_box_radius")]
+    pub active_box_radius: f32,
+    // Opacity of the words that are not being spoken (1.0 = no dimming)
+    #[serde(default = "default_inactive_word_opacity")]
+    pub inactive_word_opacity: f32,
+    // How transcript words are grouped into caption pages
+    #[serde(default)]
+    pub chunking: CaptionChunking,
+    // Style overrides for speaker-labelled words (podcasts, duo streams)
+    #[serde(default)]
+    pub speaker_styles: Vec<SpeakerStyle>,
+    // Keyword emphasis and emoji rules for caption words
+    #[serde(default)]
+    pub decorations: Vec<CaptionDecorationRule>,
+}
+
+// Decorates matching caption words (the first matching rule wins)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptionDecorationRule {
+    // Whole words, case-insensitive, ignoring punctuation
+    pub keywords: Vec<String>,
+    // Regex matched against each word (case-insensitive)
+    pub pattern: Option<String>,
+    // Hex text color
+    pub color: Option<String>,
+    // Scale in percent (e.g. 130)
+    pub scale: Option<f32>,
+    pub uppercase: bool,
+    // Emoji shown after the word
+    pub emoji: Option<String>,
+}
+
+// Caption style for one speaker; unset fields keep the base subtitle settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeakerStyle {
+    // Matches WordInfo.speaker
+    pub speaker: String,
+    pub text_color: Option<String>,
+    pub border1_color: Option<String>,
+    pub border2_color: Option<String>,
+    pub background_color: Option<String>,
+    pub active_word_color: Option<String>,
+    pub active_box_color: Option<String>,
+    // Horizontal caption center in percent of the frame width (e.g. over the speaker's focal region);
+    // None keeps the base position
+    pub position_x: Option<f32>,
+}
+
+impl SubtitleSettings {
+    // Base settings with a speaker's overrides applied
+    pub fn for_speaker(&self, style: &SpeakerStyle) -> SubtitleSettings {
+        let mut settings = self.clone();
+        let overrides = [
+            (&mut settings.text_color, &style.text_color),
+            (&mut settings.border1_color, &style.border1_color),
+            (&mut settings.border2_color, &style.border2_color),
+            (&mut settings.background_color, &style.background_color),
+        ];
+        for (field, value) in overrides {
+            if let Some(value) = value {
+                *field = value.clone();
+            }
+        }
+        if style.active_word_color.is_some() {
+            settings.active_word_color = style.active_word_color.clone();
+        }
+        if style.active_box_color.is_some() {
+            settings.active_box_color = style.active_box_color.clone();
+        }
+        settings
+    }
+}
+
+// Caption paging rules (max_words from the build command still caps each page)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptionChunking {
+    // End pages at sentence punctuation (and at commas once a page has a few words)
+    pub break_on_punctuation: bool,
+    // Silence (seconds) between two words that always starts a new page
+    pub pause_threshold: f64,
+    // 0 = no limit
+    pub max_chars_per_line: usize,
+    // 0 = no limit
+    pub max_lines: usize,
+    // Seconds a page should stay on screen before a punctuation break ends it
+    pub min_duration: f64,
+    // Seconds after which a page is ended at the next word
+    pub max_duration: f64,
+}
+
+impl Default for CaptionChunking {
+    fn default() -> Self {
+        Self {
+            break_on_punctuation: true,
+            pause_threshold: 0.6,
+            max_chars_per_line: 32,
+            max_lines: 2,
+            min_duration: 0.8,
+            max_duration: 3.5,
+        }
+    }
+}
+
+fn default_active_box_padding() -> f32 {
+    8.0
+}
+
+fn default_active_box_radius() -> f32 {
+    12.0
+}
+
+fn default_inactive_word_opacity() -> f32 {
+    1.0
 }
 
 // Word info structure
@@ -40,6 +165,24 @@ pub struct WordInfo {
     pub start: f64,
     pub end: f64,
     pub confidence: Option<f64>,
+    // Speaker label from the transcript or a diarization pass
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+// Word fixtures shared by the caption, censor and alignment tests
+#[cfg(test)]
+pub mod test_words {
+    use super::super::captions::ClipWord;
+    use super::WordInfo;
+
+    pub fn word(text: &str, start: f64, end: f64) -> WordInfo {
+        WordInfo { word: text.to_string(), start, end, confidence: None, speaker: None }
+    }
+
+    pub fn clip_word(text: &str, start: f64, end: f64, segment_index: usize) -> ClipWord {
+        ClipWord { word: text.to_string(), start, end, segment_index, speaker: None }
+    }
 }
 
 // Whisper segment structure
@@ -50,6 +193,30 @@ pub struct WhisperSegment {
     pub end: f64,
     pub text: String,
     pub words: Option<Vec<WordInfo>>,
+    // Speaker of the whole segment (used for its words that have no label of their own)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+// Single frame of a clip rendered with the exact export captions (subtitle preset preview)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlePreviewRequest {
+    pub video_path: String,
+    // Clip segments (start_time/end_time in source seconds), as for a build
+    pub segments: Vec<serde_json::Value>,
+    pub subtitle_settings: SubtitleSettings,
+    pub transcript_words: Vec<WordInfo>,
+    #[serde(default)]
+    pub max_words: Option<usize>,
+    // e.g. "9:16"
+    pub aspect_ratio: String,
+    // Time on the output timeline in seconds (intro included, like the build)
+    pub time: f64,
+    #[serde(default)]
+    pub intro_duration: Option<f64>,
+    #[serde(default)]
+    pub text_overlays: Vec<TextOverlayItem>,
 }
 
 // Clip building progress tracking structure
@@ -93,3 +260,268 @@ pub struct BuildSettings {
     pub output_format: String,
 }
 
+
+// Single clip entry in a batch build (build settings are shared across the batch)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchClipItem {
+    pub clip_id: String,
+    pub clip_name: String,
+    pub segments: Vec<serde_json::Value>,
+    // Per-clip export options (description, hashtags), overriding the batch-level ones
+    #[serde(default)]
+    pub export_options: Option<ClipExportOptions>,
+    // Per-clip render options (overlays), overriding the batch-level ones
+    #[serde(default)]
+    pub render_options: Option<ClipRenderOptions>,
+}
+
+// Batch-level progress tracking structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipBatchProgress {
+    pub batch_id: String,
+    pub project_id: String,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub progress: f64,
+    pub current_clip_id: Option<String>,
+    pub message: String,
+}
+
+// Batch build summary, emitted on completion and written to the batch manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipBatchSummary {
+    pub batch_id: String,
+    pub project_id: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub elapsed_seconds: f64,
+    pub manifest_path: Option<String>,
+    pub results: Vec<ClipBuildResult>,
+}
+
+// Optional extra files written next to each built clip
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipExportOptions {
+    // Caption sidecar formats to write ("srt", "vtt", "ttml")
+    pub caption_formats: Vec<String>,
+    // Also mux the captions into each output as a soft subtitle track (mov_text/webvtt/srt)
+    pub soft_subtitles: bool,
+    // Additional caption languages (translations) and how they are delivered
+    pub caption_tracks: Vec<CaptionTrack>,
+    pub caption_track_output: CaptionTrackOutput,
+    pub description: Option<String>,
+    pub hashtags: Vec<String>,
+}
+
+// Captions in one language, timed against the source video like the transcript.
+// Word timings are used when present, otherwise words are spread across each segment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionTrack {
+    // ISO 639 / BCP-47 language code ("es", "ja", "ar"); also tags the soft subtitle track
+    pub language: String,
+    #[serde(default)]
+    pub words: Vec<WordInfo>,
+    #[serde(default)]
+    pub segments: Vec<WhisperSegment>,
+}
+
+// How caption tracks are delivered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionTrackOutput {
+    // Soft subtitle tracks muxed into every output
+    #[default]
+    Soft,
+    // An extra output per language and aspect ratio with the captions burned in
+    Burned,
+}
+
+// Extra render options applied in the final encode pass of a clip build
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipRenderOptions {
+    pub overlays: Vec<OverlaySpec>,
+    pub text_overlays: Vec<TextOverlayItem>,
+    pub censor: Option<CensorOptions>,
+    // Snap transcript word times to speech boundaries in the clip audio before captions are generated
+    pub align_words: bool,
+    // Noise reduction and voice processing of the clip audio
+    pub speech_enhancement: Option<SpeechEnhancement>,
+}
+
+// Speech enhancement filter settings; each stage is off at its default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeechEnhancement {
+    // High-pass cutoff removing rumble and hum, 0 = off
+    pub highpass_hz: u32,
+    pub noise_reduction: NoiseReduction,
+    // Strength of the FFT denoiser in dB
+    pub noise_reduction_db: f64,
+    // De-esser intensity (0-1), 0 = off
+    pub deesser: f64,
+    // Even out loud and quiet speech
+    pub compressor: bool,
+}
+
+impl Default for SpeechEnhancement {
+    fn default() -> Self {
+        Self {
+            highpass_hz: 0,
+            noise_reduction: NoiseReduction::None,
+            noise_reduction_db: 12.0,
+            deesser: 0.0,
+            compressor: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseReduction {
+    #[default]
+    None,
+    // Spectral denoiser (afftdn): steady noise such as mic hiss and fans
+    Fft,
+}
+
+// Short sample of a video's audio rendered with and without speech enhancement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechEnhancementPreviewRequest {
+    pub video_path: String,
+    pub start_time: f64,
+    #[serde(default)]
+    pub duration: Option<f64>,
+    pub settings: SpeechEnhancement,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechEnhancementPreview {
+    pub original_path: String,
+    pub enhanced_path: String,
+    // Filter chain the enhanced sample was rendered with
+    pub filter: Option<String>,
+}
+
+// Profanity censoring: matched words are masked in captions and bleeped/muted in the audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CensorOptions {
+    // Words to censor, case-insensitive; a trailing * matches any ending ("fuck*")
+    pub words: Vec<String>,
+    pub caption_mode: CensorCaptionMode,
+    // Used by the emoji caption mode
+    pub replacement: String,
+    pub audio_mode: CensorAudioMode,
+    pub bleep_frequency: u32,
+    // Extra time muted/bleeped on each side of a word
+    pub padding_ms: u32,
+}
+
+impl Default for CensorOptions {
+    fn default() -> Self {
+        Self {
+            words: Vec::new(),
+            caption_mode: CensorCaptionMode::Mask,
+            replacement: "🤬".to_string(),
+            audio_mode: CensorAudioMode::Bleep,
+            bleep_frequency: 1000,
+            padding_ms: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CensorCaptionMode {
+    // Keep the first and last letter: "f**k"
+    #[default]
+    Mask,
+    // Replace the word with the replacement emoji
+    Emoji,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CensorAudioMode {
+    // Silence the word and play a sine tone over it
+    #[default]
+    Bleep,
+    Mute,
+    None,
+}
+
+// Timed text item (hook headline, end-card CTA) rendered on its own track above the captions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextOverlayItem {
+    pub text: String,
+    // Times on the output timeline in seconds (intro included)
+    pub start: f64,
+    pub end: f64,
+    pub style: SubtitleSettings,
+    #[serde(default)]
+    pub fade_in_ms: u32,
+    #[serde(default)]
+    pub fade_out_ms: u32,
+}
+
+// Image or animated overlay (logo, watermark) composited onto the output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlaySpec {
+    // PNG/WebP image or video with alpha (e.g. ProRes 4444 MOV)
+    pub path: String,
+    // Placement used when no aspect-ratio specific placement is given
+    #[serde(default)]
+    pub placement: OverlayPlacement,
+    // Per aspect ratio placements, keyed like the build aspect ratios ("9:16", "16:9")
+    #[serde(default)]
+    pub placements: std::collections::HashMap<String, OverlayPlacement>,
+    #[serde(default = "default_overlay_opacity")]
+    pub opacity: f32,
+    // Visibility window on the output timeline (seconds); open-ended when unset
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    // Loop animated overlays for the whole clip instead of playing once
+    #[serde(default)]
+    pub loop_animation: bool,
+}
+
+fn default_overlay_opacity() -> f32 {
+    1.0
+}
+
+// Where an overlay sits on the output frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlayPlacement {
+    // top-left, top, top-right, left, center, right, bottom-left, bottom, bottom-right
+    pub anchor: String,
+    // Margin from the anchor as a percentage of the output width/height
+    pub offset_x: f32,
+    pub offset_y: f32,
+    // Overlay width as a fraction of the output width
+    pub scale: f32,
+}
+
+impl Default for OverlayPlacement {
+    fn default() -> Self {
+        OverlayPlacement {
+            anchor: "top-right".to_string(),
+            offset_x: 3.0,
+            offset_y: 3.0,
+            scale: 0.15,
+        }
+    }
+}
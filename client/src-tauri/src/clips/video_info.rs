@@ -9,8 +9,23 @@ use super::types::AspectRatio;
 static VIDEO_INFO_CACHE: Lazy<Arc<Mutex<HashMap<String, crate::ffmpeg_utils::VideoInfo>>>> = 
     Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
 
-// Intro/outro processing cache per build session
-pub type IntroOutroCache = HashMap<(String, String, u32, u32, u32), std::path::PathBuf>;
+// Intro/outro cache key: (source path, aspect ratio, frame rate, crop width, crop height)
+pub type IntroOutroKey = (String, String, u32, u32, u32);
+
+// Intro/outro processing cache per build session (a single clip build or a whole batch).
+// Prepared files live in the artifact cache; each key has its own async slot so concurrent
+// builds in the session wait for one preparation instead of racing.
+#[derive(Default)]
+pub struct IntroOutroCache {
+    slots: HashMap<IntroOutroKey, Arc<tokio::sync::Mutex<Option<std::path::PathBuf>>>>,
+}
+
+impl IntroOutroCache {
+    // Get (or create) the slot for a key; lock the slot while preparing the file
+    pub fn slot(&mut self, key: &IntroOutroKey) -> Arc<tokio::sync::Mutex<Option<std::path::PathBuf>>> {
+        self.slots.entry(key.clone()).or_default().clone()
+    }
+}
 
 // Helper function to parse aspect ratio string (e.g., "16:9") into AspectRatio struct
 pub fn parse_aspect_ratio(ratio_str: &str) -> Result<AspectRatio, String> {
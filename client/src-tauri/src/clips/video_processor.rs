@@ -2,10 +2,18 @@ use tauri_plugin_shell::ShellExt;
 use std::sync::{Arc, Mutex};
 use futures::future::join_all;
 
-use super::types::AspectRatio;
-use super::encoder::{detect_hardware_encoder, get_quality_settings};
+use super::types::{AspectRatio, ClipRenderOptions, OverlaySpec};
+use super::censor::audio_censor_filter;
+use super::speech_enhancement::speech_enhancement_filter;
+use super::encoder::{detect_hardware_encoder, EncoderConfig};
 use super::video_info::{get_video_info, calculate_crop_params, calculate_crop_position, IntroOutroCache};
 use super::font_manager::get_fonts_dir;
+use super::font_registry::font_registry;
+use super::subtitle::ass_font_families;
+use super::scheduler::{acquire_ffmpeg_slots, encode_job_cost, copy_job_cost};
+use super::artifact_cache::{artifact_cache, artifact_key, source_fingerprint, link_or_copy};
+use super::manifest::sha256_file;
+use super::overlay::{build_overlay_filter_graph, overlay_input_args};
 
 // Build single-segment clip with aspect ratio and quality settings
 // Note: output_format is unused here because the path already has the correct extension
@@ -14,16 +22,38 @@ pub async fn build_single_segment_clip_with_settings(
     video_path: &str,
     output_path: &std::path::Path,
     segment: &serde_json::Value,
-    subtitle_path: Option<&std::path::Path>,
+    subtitle_paths: &[std::path::PathBuf],  // ASS files burned in order (captions, then text overlays)
     aspect_ratio: &AspectRatio,
     quality: &str,
     frame_rate: u32,
-    _output_format: &str,  // Format already applied in output_path extension
+    output_format: &str,  // Format already applied in output_path extension
     intro_path: Option<&str>,
     outro_path: Option<&str>,
+    render_options: Option<&ClipRenderOptions>,
+    censor_intervals: &[(f64, f64)],  // Source-time ranges to bleep/mute (see censor.rs)
     intro_outro_cache: Arc<Mutex<IntroOutroCache>>
 ) -> Result<(), String> {
-    let shell = app.shell();
+    // If intro or outro is present, we need to use the concat approach
+    if intro_path.is_some() || outro_path.is_some() {
+        println!("[Rust] Intro or outro detected, using concat approach for single segment");
+        return build_multi_segment_clip_with_settings(
+            app,
+            video_path,
+            output_path,
+            std::slice::from_ref(segment),
+            subtitle_paths,
+            aspect_ratio,
+            quality,
+            frame_rate,
+            output_format,
+            intro_path,
+            outro_path,
+            render_options,
+            censor_intervals,
+            intro_outro_cache
+        ).await;
+    }
+
     let start_time: f64 = segment["start_time"].as_f64().ok_or("Invalid start_time")?;
     let end_time: f64 = segment["end_time"].as_f64().ok_or("Invalid end_time")?;
     let duration = end_time - start_time;
@@ -33,298 +63,67 @@ pub async fn build_single_segment_clip_with_settings(
     // Get video info for cropping
     let video_info = get_video_info(app, video_path).await?;
     let (crop_w, crop_h, crop_x, crop_y) = calculate_crop_params(video_info.width, video_info.height, aspect_ratio);
-    
-    // Get quality settings (unused in this path, but kept for reference)
-    let (_preset, _crf) = get_quality_settings(quality);
-    
-    // If intro or outro is present, we need to use the concat approach
-    if intro_path.is_some() || outro_path.is_some() {
-        println!("[Rust] Intro or outro detected, using concat approach for single segment");
-        
-        // Get storage paths for temporary files
-        let paths = crate::storage::init_storage_dirs()
-            .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let crop_filter = format!("crop={}:{}:{}:{}", crop_w, crop_h, crop_x, crop_y);
 
-        let temp_dir = paths.temp.join(format!("clip_single_segment_{}", uuid::Uuid::new_v4()));
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    // Detect hardware encoder for better performance
+    let encoder = detect_hardware_encoder(app, quality).await;
+
+    // Crop + overlays + subtitles happen in ONE PASS here, so the whole render is a single cached stage
+    let overlays = overlays_of(render_options);
+    let enhancement = enhancement_filter_of(render_options);
+    let audio_filter = audio_filter_of(enhancement.as_deref(), render_options, censor_intervals, start_time, end_time);
+    let source = source_fingerprint(video_path).await?;
+    let key = artifact_key("single", &[
+        source,
+        format!("{:.3}-{:.3}", start_time, end_time),
+        crop_filter.clone(),
+        encoder_fingerprint(&encoder, frame_rate),
+        subtitle_fingerprint(subtitle_paths)?,
+        subtitle_font_fingerprint(app, subtitle_paths).await?,
+        overlay_fingerprint(overlays).await?,
+        audio_filter.clone().unwrap_or_else(|| "none".to_string()),
+    ]);
 
-        // Detect hardware encoder for better performance
-        let encoder = detect_hardware_encoder(app, quality).await;
+    let cache = artifact_cache()?;
+    let rendered = cache.get_or_create(&key, &output_extension(output_path), |tmp_path| async move {
+        // Get fonts directory for subtitle rendering
+        let fonts_dir = get_fonts_dir(app).ok();
 
-        // Extract the main segment without subtitles (we'll add them later if needed)
-        let segment_file = temp_dir.join("main_segment.mp4");
-        let crop_filter = format!("crop={}:{}:{}:{}", crop_w, crop_h, crop_x, crop_y);
+        // Build video filter combining crop + overlays + subtitles in ONE PASS
+        // Force RGB24 for accurate subtitle color rendering before applying ASS
+        let mut post_parts = vec!["format=rgb24".to_string()];
+        for path in subtitle_paths {
+            post_parts.push(ass_filter(path, fonts_dir.as_deref()));
+        }
 
-        // Build encoder-specific args
         let mut args = vec![
             "-ss".to_string(), format!("{:.3}", start_time),
             "-i".to_string(), video_path.to_string(),
-            "-t".to_string(), format!("{:.3}", duration),
-            "-vf".to_string(), crop_filter.clone(),
-            "-c:v".to_string(), encoder.codec.clone(),
         ];
-        
-        // Add preset if applicable
-        if let Some(enc_preset) = &encoder.preset {
-            args.push("-preset".to_string());
-            args.push(enc_preset.clone());
+        args.extend(overlay_input_args(overlays));
+        args.extend_from_slice(&["-t".to_string(), format!("{:.3}", duration)]);
+        args.extend(video_filter_args(&crop_filter, Some(&post_parts.join(",")), overlays, aspect_ratio, crop_w, crop_h));
+        args.extend(encoder_args(&encoder));
+        if let Some(filter) = &audio_filter {
+            args.extend_from_slice(&["-af".to_string(), filter.clone()]);
         }
-        
-        // Add quality parameter
-        args.push(encoder.quality_param.clone());
-        args.push(encoder.quality_value.clone());
-        
+
         // Add common parameters
         args.extend_from_slice(&[
             "-r".to_string(), frame_rate.to_string(),
             "-c:a".to_string(), "aac".to_string(),
             "-b:a".to_string(), "192k".to_string(),
             "-pix_fmt".to_string(), "yuv420p".to_string(),
+            "-movflags".to_string(), "+faststart".to_string(),
             "-avoid_negative_ts".to_string(), "1".to_string(),
             "-y".to_string(),
-            segment_file.to_string_lossy().to_string(),
+            tmp_path.to_string_lossy().to_string(),
         ]);
 
-        let output = shell.sidecar("ffmpeg")
-            .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-            .args(args)
-            .output()
-            .await
-            .map_err(|e| format!("Failed to extract segment: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Failed to extract segment: {}", stderr));
-        }
-
-        // Process intro and outro if provided
-        let mut intro_file: Option<std::path::PathBuf> = None;
-        let mut outro_file: Option<std::path::PathBuf> = None;
-
-        if let Some(intro) = intro_path {
-            println!("[Rust] Processing intro video...");
-            intro_file = Some(prepare_intro_outro_for_concat(
-                app,
-                intro,
-                &temp_dir,
-                "intro",
-                aspect_ratio,
-                quality,
-                frame_rate,
-                crop_w,
-                crop_h,
-                intro_outro_cache.clone()
-            ).await?);
-        }
-
-        if let Some(outro) = outro_path {
-            println!("[Rust] Processing outro video...");
-            outro_file = Some(prepare_intro_outro_for_concat(
-                app,
-                outro,
-                &temp_dir,
-                "outro",
-                aspect_ratio,
-                quality,
-                frame_rate,
-                crop_w,
-                crop_h,
-                intro_outro_cache.clone()
-            ).await?);
-        }
-
-        // Create concat list file
-        let concat_file = temp_dir.join("concat_list.txt");
-        let mut concat_content = String::new();
-        
-        // Add intro if present
-        if let Some(intro_path) = &intro_file {
-            concat_content.push_str(&format!("file '{}'\n", intro_path.display()));
-        }
-        
-        // Add main segment
-        concat_content.push_str(&format!("file '{}'\n", segment_file.display()));
-        
-        // Add outro if present
-        if let Some(outro_path) = &outro_file {
-            concat_content.push_str(&format!("file '{}'\n", outro_path.display()));
-        }
-
-        std::fs::write(&concat_file, concat_content)
-            .map_err(|e| format!("Failed to write concat file: {}", e))?;
-
-        // Concatenate files
-        let concat_output_path = if subtitle_path.is_some() {
-            temp_dir.join("concat_output.mp4")
-        } else {
-            output_path.to_path_buf()
-        };
-
-        let output = shell.sidecar("ffmpeg")
-            .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-            .args([
-                "-f", "concat",
-                "-safe", "0",
-                "-i", concat_file.to_str().ok_or("Invalid concat file path")?,
-                "-c", "copy",
-                "-avoid_negative_ts", "1",
-                "-y",
-                concat_output_path.to_str().ok_or("Invalid output path")?,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to concatenate: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("FFmpeg concatenation failed: {}", stderr));
-        }
-
-        // If subtitles are present, burn them now
-        if let Some(sub_path) = subtitle_path {
-            println!("[Rust] Burning subtitles with hardware acceleration...");
-            
-            // Get fonts directory
-            let fonts_dir_for_burn = get_fonts_dir(app).ok();
-            
-            let sub_arg = sub_path.to_string_lossy().replace("\\", "/").replace(":", "\\:");
-            
-            // Build ass filter with fontsdir parameter
-            let vf_arg = if let Some(fdir) = fonts_dir_for_burn {
-                let fonts_dir_str = fdir.to_string_lossy().replace("\\", "/").replace(":", "\\:");
-                format!("format=rgb24,ass='{}':fontsdir='{}'", sub_arg, fonts_dir_str)
-            } else {
-                format!("format=rgb24,ass='{}'", sub_arg)
-            };
-
-            // Set fontconfig path for FFmpeg to find our custom fonts
-            let fontconfig_path = paths.temp.join("fonts.conf");
-            
-            // Build encoder-specific args
-            let mut subtitle_args = vec![
-                "-i".to_string(), concat_output_path.to_string_lossy().to_string(),
-                "-vf".to_string(), vf_arg.clone(),
-                "-c:v".to_string(), encoder.codec.clone(),
-            ];
-            
-            // Add preset if applicable
-            if let Some(enc_preset) = &encoder.preset {
-                subtitle_args.push("-preset".to_string());
-                subtitle_args.push(enc_preset.clone());
-            }
-            
-            // Add quality parameter
-            subtitle_args.push(encoder.quality_param.clone());
-            subtitle_args.push(encoder.quality_value.clone());
-            
-            // Add common parameters
-            subtitle_args.extend_from_slice(&[
-                "-c:a".to_string(), "aac".to_string(),
-                "-b:a".to_string(), "192k".to_string(),
-                "-pix_fmt".to_string(), "yuv420p".to_string(),
-                "-movflags".to_string(), "+faststart".to_string(),
-                "-y".to_string(),
-                output_path.to_string_lossy().to_string(),
-            ]);
-
-            let output = shell.sidecar("ffmpeg")
-                .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-                .env("FONTCONFIG_FILE", fontconfig_path.to_string_lossy().to_string())
-                .args(subtitle_args)
-                .output()
-                .await
-                .map_err(|e| format!("Failed to burn subtitles: {}", e))?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("FFmpeg subtitle burning failed: {}", stderr));
-            }
-        }
-
-        // Clean up temporary files
-        let _ = std::fs::remove_dir_all(&temp_dir);
-
-        return Ok(());
-    }
-
-    // Original single-segment path (no intro/outro)
-    // Detect hardware encoder for better performance
-    let encoder = detect_hardware_encoder(app, quality).await;
-    
-    // Get fonts directory for subtitle rendering
-    let fonts_dir = get_fonts_dir(app).ok();
-
-    // Build video filter combining crop + subtitles in ONE PASS
-    // Force RGB24 for accurate subtitle color rendering before applying ASS
-    let mut vf_parts = vec![
-        format!("crop={}:{}:{}:{}", crop_w, crop_h, crop_x, crop_y),
-        "format=rgb24".to_string()
-    ];
-    
-    if let Some(path) = subtitle_path {
-        let path_str = path.to_string_lossy().replace("\\", "/").replace(":", "\\:");
-        // Add fonts directory parameter to ass filter
-        if let Some(ref fdir) = fonts_dir {
-            let fonts_dir_str = fdir.to_string_lossy().replace("\\", "/").replace(":", "\\:");
-            vf_parts.push(format!("ass='{}':fontsdir='{}'", path_str, fonts_dir_str));
-        } else {
-            vf_parts.push(format!("ass='{}'", path_str));
-        }
-    }
-    
-    let vf_arg = vf_parts.join(",");
-
-    // Build encoder-specific args
-    let mut args = vec![
-        "-ss".to_string(), format!("{:.3}", start_time),
-        "-i".to_string(), video_path.to_string(),
-        "-t".to_string(), format!("{:.3}", duration),
-        "-vf".to_string(), vf_arg,
-        "-c:v".to_string(), encoder.codec.clone(),
-    ];
-    
-    // Add preset if applicable
-    if let Some(preset) = &encoder.preset {
-        args.push("-preset".to_string());
-        args.push(preset.clone());
-    }
-    
-    // Add quality parameter
-    args.push(encoder.quality_param.clone());
-    args.push(encoder.quality_value.clone());
-    
-    // Add common parameters
-    args.extend_from_slice(&[
-        "-r".to_string(), frame_rate.to_string(),
-        "-c:a".to_string(), "aac".to_string(),
-        "-b:a".to_string(), "192k".to_string(),
-        "-pix_fmt".to_string(), "yuv420p".to_string(),
-        "-movflags".to_string(), "+faststart".to_string(),
-        "-avoid_negative_ts".to_string(), "1".to_string(),
-        "-y".to_string(),
-        output_path.to_string_lossy().to_string(),
-    ]);
-
-    // Set fontconfig path for FFmpeg to find our custom fonts
-    let fontconfig_path = crate::storage::init_storage_dirs()
-        .map_err(|e| format!("Failed to get storage paths: {}", e))?
-        .temp.join("fonts.conf");
-
-    let output = shell.sidecar("ffmpeg")
-        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-        .env("FONTCONFIG_FILE", fontconfig_path.to_string_lossy().to_string())
-        .args(args)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg failed: {}", stderr));
-    }
+        run_ffmpeg(app, args, encode_job_cost(&encoder), !subtitle_paths.is_empty(), "FFmpeg").await
+    }).await?;
 
-    Ok(())
+    link_or_copy(&rendered, output_path)
 }
 
 // Build multi-segment clip with aspect ratio and quality settings
@@ -334,25 +133,17 @@ pub async fn build_multi_segment_clip_with_settings(
     video_path: &str,
     output_path: &std::path::Path,
     segments: &[serde_json::Value],
-    subtitle_path: Option<&std::path::Path>,
+    subtitle_paths: &[std::path::PathBuf],  // ASS files burned in order (captions, then text overlays)
     aspect_ratio: &AspectRatio,
     quality: &str,
     frame_rate: u32,
     _output_format: &str,  // Format already applied in output_path extension
     intro_path: Option<&str>,
     outro_path: Option<&str>,
+    render_options: Option<&ClipRenderOptions>,
+    censor_intervals: &[(f64, f64)],  // Source-time ranges to bleep/mute (see censor.rs)
     intro_outro_cache: Arc<Mutex<IntroOutroCache>>
 ) -> Result<(), String> {
-    let shell = app.shell();
-
-    // Get storage paths for temporary files
-    let paths = crate::storage::init_storage_dirs()
-        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
-
-    let temp_dir = paths.temp.join(format!("clip_segments_{}", uuid::Uuid::new_v4()));
-    std::fs::create_dir_all(&temp_dir)
-        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
-
     println!("[Rust] Building {} segments with aspect ratio {}:{}", segments.len(), aspect_ratio.width, aspect_ratio.height);
 
     // Get video info for cropping
@@ -360,77 +151,32 @@ pub async fn build_multi_segment_clip_with_settings(
     let (crop_w, crop_h, crop_x, crop_y) = calculate_crop_params(video_info.width, video_info.height, aspect_ratio);
     let crop_filter = format!("crop={}:{}:{}:{}", crop_w, crop_h, crop_x, crop_y);
 
-    // Get quality settings (unused in this path, but kept for reference)
-    let (_preset, _crf) = get_quality_settings(quality);
-    
     // Detect hardware encoder for better performance
     let encoder = detect_hardware_encoder(app, quality).await;
+    let source = source_fingerprint(video_path).await?;
+    let enhancement = enhancement_filter_of(render_options);
 
-    // Extract segments with cropping IN PARALLEL for speed
+    // Extract segments with cropping IN PARALLEL for speed (unchanged segments come from the cache)
     println!("[Rust] Extracting {} segments in parallel...", segments.len());
     let segment_tasks: Vec<_> = segments.iter().enumerate().map(|(i, segment)| {
         let start_time: f64 = segment["start_time"].as_f64().unwrap_or(0.0);
         let end_time: f64 = segment["end_time"].as_f64().unwrap_or(0.0);
-        let duration = end_time - start_time;
-        let segment_file = temp_dir.join(format!("segment_{:03}.mp4", i));
         let crop_filter = crop_filter.clone();
-        let video_path = video_path.to_string();
-        let app = app.clone();
+        let source = source.clone();
         let encoder = encoder.clone();
-        let frame_rate_str = frame_rate.to_string();
+        // Enhancement and censoring happen per segment in source time, so intro/outro audio is never touched
+        let audio_filter = audio_filter_of(enhancement.as_deref(), render_options, censor_intervals, start_time, end_time);
 
         async move {
-            let shell = app.shell();
-            
-            // Build encoder-specific args
-            let mut args = vec![
-                "-ss".to_string(), format!("{:.3}", start_time),
-                "-i".to_string(), video_path.clone(),
-                "-t".to_string(), format!("{:.3}", duration),
-                "-vf".to_string(), crop_filter.clone(),
-                "-c:v".to_string(), encoder.codec.clone(),
-            ];
-            
-            // Add preset if applicable
-            if let Some(preset) = &encoder.preset {
-                args.push("-preset".to_string());
-                args.push(preset.clone());
-            }
-            
-            // Add quality parameter
-            args.push(encoder.quality_param.clone());
-            args.push(encoder.quality_value.clone());
-            
-            // Add common parameters
-            args.extend_from_slice(&[
-                "-r".to_string(), frame_rate_str.clone(),
-                "-c:a".to_string(), "aac".to_string(),
-                "-b:a".to_string(), "192k".to_string(),
-                "-pix_fmt".to_string(), "yuv420p".to_string(),
-                "-avoid_negative_ts".to_string(), "1".to_string(),
-                "-y".to_string(),
-                segment_file.to_string_lossy().to_string(),
-            ]);
-            
-            let output = shell.sidecar("ffmpeg")
-                .map_err(|e| format!("Failed to get ffmpeg sidecar for segment {}: {}", i, e))?
-                .args(args)
-                .output()
+            encode_cropped_segment(app, video_path, &source, start_time, end_time, &crop_filter, &encoder, frame_rate, audio_filter.as_deref())
                 .await
-                .map_err(|e| format!("Failed to extract segment {}: {}", i, e))?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("Failed to extract segment {}: {}", i, stderr));
-            }
-
-            Ok::<std::path::PathBuf, String>(segment_file)
+                .map_err(|e| format!("Failed to extract segment {}: {}", i, e))
         }
     }).collect();
 
     // Wait for all segments to complete in parallel
     let segment_results = join_all(segment_tasks).await;
-    
+
     // Check for errors and collect successful segment files
     let mut segment_files = Vec::new();
     for (i, result) in segment_results.into_iter().enumerate() {
@@ -439,7 +185,7 @@ pub async fn build_multi_segment_clip_with_settings(
             Err(e) => return Err(format!("Segment {} failed: {}", i, e)),
         }
     }
-    
+
     println!("[Rust] All {} segments extracted successfully", segment_files.len());
 
     // Process intro and outro if provided
@@ -451,7 +197,6 @@ pub async fn build_multi_segment_clip_with_settings(
         intro_file = Some(prepare_intro_outro_for_concat(
             app,
             intro,
-            &temp_dir,
             "intro",
             aspect_ratio,
             quality,
@@ -467,7 +212,6 @@ pub async fn build_multi_segment_clip_with_settings(
         outro_file = Some(prepare_intro_outro_for_concat(
             app,
             outro,
-            &temp_dir,
             "outro",
             aspect_ratio,
             quality,
@@ -478,93 +222,57 @@ pub async fn build_multi_segment_clip_with_settings(
         ).await?);
     }
 
-    // Create concat list file with intro, segments, and outro
-    let concat_file = temp_dir.join("concat_list.txt");
-    let mut concat_content = String::new();
-    
-    // Add intro if present
-    if let Some(intro_path) = &intro_file {
-        concat_content.push_str(&format!("file '{}'\n", intro_path.display()));
-    }
-    
-    // Add main clip segments
-    for segment_file in &segment_files {
-        concat_content.push_str(&format!("file '{}'\n", segment_file.display()));
-    }
-    
-    // Add outro if present
-    if let Some(outro_path) = &outro_file {
-        concat_content.push_str(&format!("file '{}'\n", outro_path.display()));
-    }
+    // Intro, segments, and outro in playback order
+    let mut pieces: Vec<std::path::PathBuf> = Vec::new();
+    pieces.extend(intro_file);
+    pieces.extend(segment_files);
+    pieces.extend(outro_file);
 
-    std::fs::write(&concat_file, concat_content)
-        .map_err(|e| format!("Failed to write concat file: {}", e))?;
+    // Concatenate into the base video (cached - only changes when a piece changes)
+    let base_video = concat_pieces(app, &pieces).await?;
 
-    // Concatenate segments
-    let concat_output_path = if subtitle_path.is_some() {
-        temp_dir.join("concat_output.mp4")
-    } else {
-        output_path.to_path_buf()
-    };
+    // Without subtitles or overlays the base video is the final output
+    let overlays = overlays_of(render_options);
+    if subtitle_paths.is_empty() && overlays.is_empty() {
+        link_or_copy(&base_video, output_path)?;
+        println!("[Rust] Multi-segment build successful");
+        return Ok(());
+    }
 
-    let output = shell.sidecar("ffmpeg")
-        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-        .args([
-            "-f", "concat",
-            "-safe", "0",
-            "-i", concat_file.to_str().ok_or("Invalid concat file path")?,
-            "-c", "copy",
-            "-avoid_negative_ts", "1",
-            "-y",
-            concat_output_path.to_str().ok_or("Invalid output path")?,
-        ])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to concatenate segments: {}", e))?;
+    // Burn subtitles and composite overlays in a final pass (cached by base video + subtitle/overlay content)
+    let key = artifact_key("final", &[
+        artifact_name(&base_video),
+        encoder_fingerprint(&encoder, frame_rate),
+        subtitle_fingerprint(subtitle_paths)?,
+        subtitle_font_fingerprint(app, subtitle_paths).await?,
+        overlay_fingerprint(overlays).await?,
+    ]);
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg concatenation failed: {}", stderr));
-    }
+    let cache = artifact_cache()?;
+    let rendered = cache.get_or_create(&key, &output_extension(output_path), |tmp_path| async move {
+        println!("[Rust] Burning subtitles/overlays with hardware acceleration...");
 
-    // If subtitles are present, burn them now with hardware acceleration
-    if let Some(sub_path) = subtitle_path {
-        println!("[Rust] Burning subtitles with hardware acceleration...");
-        
         // Get fonts directory for multi-segment path
         let fonts_dir_for_burn = get_fonts_dir(app).ok();
-        
-        let sub_arg = sub_path.to_string_lossy().replace("\\", "/").replace(":", "\\:");
-        
-        // Build ass filter with fontsdir parameter
+
         // Force RGB24 for accurate subtitle color rendering
-        let vf_arg = if let Some(fdir) = fonts_dir_for_burn {
-            let fonts_dir_str = fdir.to_string_lossy().replace("\\", "/").replace(":", "\\:");
-            format!("format=rgb24,ass='{}':fontsdir='{}'", sub_arg, fonts_dir_str)
+        let post_filter = if subtitle_paths.is_empty() {
+            None
         } else {
-            format!("format=rgb24,ass='{}'", sub_arg)
+            let ass_filters: Vec<String> = subtitle_paths
+                .iter()
+                .map(|sub_path| ass_filter(sub_path, fonts_dir_for_burn.as_deref()))
+                .collect();
+            Some(format!("format=rgb24,{}", ass_filters.join(",")))
         };
 
-        // Set fontconfig path for FFmpeg to find our custom fonts
-        let fontconfig_path = paths.temp.join("fonts.conf");
-        
-        // Build encoder-specific args
         let mut subtitle_args = vec![
-            "-i".to_string(), concat_output_path.to_string_lossy().to_string(),
-            "-vf".to_string(), vf_arg.clone(),
-            "-c:v".to_string(), encoder.codec.clone(),
+            "-i".to_string(), base_video.to_string_lossy().to_string(),
         ];
-        
-        // Add preset if applicable
-        if let Some(enc_preset) = &encoder.preset {
-            subtitle_args.push("-preset".to_string());
-            subtitle_args.push(enc_preset.clone());
-        }
-        
-        // Add quality parameter
-        subtitle_args.push(encoder.quality_param.clone());
-        subtitle_args.push(encoder.quality_value.clone());
-        
+        subtitle_args.extend(overlay_input_args(overlays));
+        subtitle_args.extend(video_filter_args("", post_filter.as_deref(), overlays, aspect_ratio, crop_w, crop_h));
+        subtitle_args.extend(encoder_args(&encoder));
+
         // Add common parameters
         subtitle_args.extend_from_slice(&[
             "-c:a".to_string(), "aac".to_string(),
@@ -572,37 +280,106 @@ pub async fn build_multi_segment_clip_with_settings(
             "-pix_fmt".to_string(), "yuv420p".to_string(),
             "-movflags".to_string(), "+faststart".to_string(),
             "-y".to_string(),
-            output_path.to_string_lossy().to_string(),
+            tmp_path.to_string_lossy().to_string(),
         ]);
 
-        let output = shell.sidecar("ffmpeg")
-            .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
-            .env("FONTCONFIG_FILE", fontconfig_path.to_string_lossy().to_string())
-            .args(subtitle_args)
-            .output()
-            .await
-            .map_err(|e| format!("Failed to burn subtitles: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("FFmpeg subtitle burning failed: {}", stderr));
-        }
-    }
+        run_ffmpeg(app, subtitle_args, encode_job_cost(&encoder), !subtitle_paths.is_empty(), "FFmpeg subtitle burning").await
+    }).await?;
 
-    // Clean up temporary files
-    let _ = std::fs::remove_dir_all(&temp_dir);
-    println!("[Rust] Multi-segment build successful, cleaned up temp files");
+    link_or_copy(&rendered, output_path)?;
+    println!("[Rust] Multi-segment build successful");
 
     Ok(())
 }
 
+// Extract one source range with the aspect-ratio crop applied (cached per range/crop/encoder)
+async fn encode_cropped_segment(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    source: &str,
+    start_time: f64,
+    end_time: f64,
+    crop_filter: &str,
+    encoder: &EncoderConfig,
+    frame_rate: u32,
+    audio_filter: Option<&str>
+) -> Result<std::path::PathBuf, String> {
+    let duration = end_time - start_time;
+    let key = artifact_key("segment", &[
+        source.to_string(),
+        format!("{:.3}-{:.3}", start_time, end_time),
+        crop_filter.to_string(),
+        encoder_fingerprint(encoder, frame_rate),
+        audio_filter.unwrap_or("none").to_string(),
+    ]);
+
+    artifact_cache()?.get_or_create(&key, "mp4", |tmp_path| async move {
+        let mut args = vec![
+            "-ss".to_string(), format!("{:.3}", start_time),
+            "-i".to_string(), video_path.to_string(),
+            "-t".to_string(), format!("{:.3}", duration),
+            "-vf".to_string(), crop_filter.to_string(),
+        ];
+        args.extend(encoder_args(encoder));
+        if let Some(filter) = audio_filter {
+            args.extend_from_slice(&["-af".to_string(), filter.to_string()]);
+        }
+
+        // Add common parameters
+        args.extend_from_slice(&[
+            "-r".to_string(), frame_rate.to_string(),
+            "-c:a".to_string(), "aac".to_string(),
+            "-b:a".to_string(), "192k".to_string(),
+            "-pix_fmt".to_string(), "yuv420p".to_string(),
+            "-avoid_negative_ts".to_string(), "1".to_string(),
+            "-y".to_string(),
+            tmp_path.to_string_lossy().to_string(),
+        ]);
+
+        run_ffmpeg(app, args, encode_job_cost(encoder), false, "Segment extraction").await
+    }).await
+}
+
+// Concatenate prepared pieces without re-encoding (cached by the list of pieces)
+async fn concat_pieces(
+    app: &tauri::AppHandle,
+    pieces: &[std::path::PathBuf]
+) -> Result<std::path::PathBuf, String> {
+    let key = artifact_key("concat", &pieces.iter().map(|p| artifact_name(p)).collect::<Vec<_>>());
+
+    artifact_cache()?.get_or_create(&key, "mp4", |tmp_path| async move {
+        // Create concat list file next to the partial output
+        let concat_file = tmp_path.with_extension("txt");
+        let concat_content: String = pieces
+            .iter()
+            .map(|piece| format!("file '{}'\n", piece.display()))
+            .collect();
+
+        std::fs::write(&concat_file, concat_content)
+            .map_err(|e| format!("Failed to write concat file: {}", e))?;
+
+        let args = vec![
+            "-f".to_string(), "concat".to_string(),
+            "-safe".to_string(), "0".to_string(),
+            "-i".to_string(), concat_file.to_string_lossy().to_string(),
+            "-c".to_string(), "copy".to_string(),
+            "-avoid_negative_ts".to_string(), "1".to_string(),
+            "-y".to_string(),
+            tmp_path.to_string_lossy().to_string(),
+        ];
+
+        let result = run_ffmpeg(app, args, copy_job_cost(), false, "FFmpeg concatenation").await;
+        let _ = std::fs::remove_file(&concat_file);
+        result
+    }).await
+}
+
 // Helper function to prepare intro/outro for concatenation with the main clip
 // This processes the intro/outro to match the aspect ratio, frame rate, and resolution
-// Includes caching to avoid re-processing the same intro/outro multiple times
+// Prepared files live in the artifact cache, so they are reused across builds and sessions
 pub async fn prepare_intro_outro_for_concat(
     app: &tauri::AppHandle,
     intro_outro_path: &str,
-    temp_dir: &std::path::Path,
     file_prefix: &str,
     aspect_ratio: &AspectRatio,
     quality: &str,
@@ -619,19 +396,22 @@ pub async fn prepare_intro_outro_for_concat(
         crop_w,
         crop_h
     );
-    
-    // Check if already processed in this build session
-    {
-        let cache_lock = cache.lock().unwrap();
-        if let Some(cached_path) = cache_lock.get(&cache_key) {
-            if cached_path.exists() {
-                println!("[Rust] Using cached {} from: {}", file_prefix, cached_path.display());
-                return Ok(cached_path.clone());
-            }
+
+    // Get the slot for this key (std lock is dropped before any await points)
+    let slot = {
+        let mut cache_lock = cache.lock().unwrap();
+        cache_lock.slot(&cache_key)
+    };
+
+    // Hold the slot while preparing so parallel builds wait instead of re-encoding
+    let mut slot_guard = slot.lock().await;
+    if let Some(cached_path) = slot_guard.as_ref() {
+        if cached_path.exists() {
+            println!("[Rust] Using cached {} from: {}", file_prefix, cached_path.display());
+            return Ok(cached_path.clone());
         }
-    } // Lock is dropped here before any await points
-    
-    let shell = app.shell();
+    }
+
     println!("[Rust] Preparing {} for concat with aspect ratio {}:{}", file_prefix, aspect_ratio.width, aspect_ratio.height);
 
     // Get video info for the intro/outro
@@ -641,61 +421,387 @@ pub async fn prepare_intro_outro_for_concat(
     // Detect hardware encoder
     let encoder = detect_hardware_encoder(app, quality).await;
 
-    // Create output path in temp directory
-    let output_path = temp_dir.join(format!("{}_processed.mp4", file_prefix));
-
     // Build crop filter
     let crop_filter = format!("crop={}:{}:{}:{}", crop_w, crop_h, crop_x, crop_y);
 
-    // Build encoder-specific args
-    let mut args = vec![
-        "-i".to_string(), intro_outro_path.to_string(),
-        "-vf".to_string(), crop_filter.clone(),
-        "-c:v".to_string(), encoder.codec.clone(),
-    ];
-    
+    let key = artifact_key("bookend", &[
+        source_fingerprint(intro_outro_path).await?,
+        crop_filter.clone(),
+        encoder_fingerprint(&encoder, frame_rate),
+    ]);
+
+    let output_path = artifact_cache()?.get_or_create(&key, "mp4", |tmp_path| async move {
+        let mut args = vec![
+            "-i".to_string(), intro_outro_path.to_string(),
+            "-vf".to_string(), crop_filter.clone(),
+        ];
+        args.extend(encoder_args(&encoder));
+
+        // Add common parameters
+        args.extend_from_slice(&[
+            "-r".to_string(), frame_rate.to_string(),
+            "-c:a".to_string(), "aac".to_string(),
+            "-b:a".to_string(), "192k".to_string(),
+            "-pix_fmt".to_string(), "yuv420p".to_string(),
+            "-avoid_negative_ts".to_string(), "1".to_string(),
+            "-y".to_string(),
+            tmp_path.to_string_lossy().to_string(),
+        ]);
+
+        run_ffmpeg(app, args, encode_job_cost(&encoder), false, &format!("FFmpeg {} processing", file_prefix)).await
+    }).await?;
+
+    println!("[Rust] Successfully processed {} to: {}", file_prefix, output_path.display());
+
+    // Remember the result in the slot for the rest of the session
+    *slot_guard = Some(output_path.clone());
+
+    Ok(output_path)
+}
+
+// Video encoder arguments (codec, preset, quality and thread count)
+fn encoder_args(encoder: &EncoderConfig) -> Vec<String> {
+    let mut args = vec!["-c:v".to_string(), encoder.codec.clone()];
+
     // Add preset if applicable
     if let Some(preset) = &encoder.preset {
         args.push("-preset".to_string());
         args.push(preset.clone());
     }
-    
+
     // Add quality parameter
     args.push(encoder.quality_param.clone());
     args.push(encoder.quality_value.clone());
-    
-    // Add common parameters
+
+    // Match FFmpeg's thread count to the CPU slots the job holds
+    args.push("-threads".to_string());
+    args.push(encode_job_cost(encoder).to_string());
+    args
+}
+
+// Cache key input describing everything about the encode settings
+fn encoder_fingerprint(encoder: &EncoderConfig, frame_rate: u32) -> String {
+    format!(
+        "{}|{}|{}={}|{}fps|aac192k|yuv420p",
+        encoder.codec,
+        encoder.preset.as_deref().unwrap_or(""),
+        encoder.quality_param,
+        encoder.quality_value,
+        frame_rate
+    )
+}
+
+// Cache key input for the subtitle files (their content hashes in burn order, or "none")
+fn subtitle_fingerprint(subtitle_paths: &[std::path::PathBuf]) -> Result<String, String> {
+    if subtitle_paths.is_empty() {
+        return Ok("none".to_string());
+    }
+    let hashes = subtitle_paths
+        .iter()
+        .map(|path| sha256_file(path))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(hashes.join(";"))
+}
+
+// Cache key input for the font files the subtitle files can render with (every face of each
+// family they name). Fonts are loaded by family at render time, so installing or replacing one
+// must change the key even when the subtitle text is the same.
+async fn subtitle_font_fingerprint(app: &tauri::AppHandle, subtitle_paths: &[std::path::PathBuf]) -> Result<String, String> {
+    if subtitle_paths.is_empty() {
+        return Ok("none".to_string());
+    }
+    let mut families: Vec<String> = Vec::new();
+    for path in subtitle_paths {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read subtitle file {}: {}", path.display(), e))?;
+        families.extend(ass_font_families(&content));
+    }
+    let fonts_dir = get_fonts_dir(app).ok();
+    let registry = font_registry(fonts_dir.as_deref());
+    let mut parts = Vec::new();
+    for family in &families {
+        for face in registry.family_faces(family) {
+            parts.push(source_fingerprint(&face.path.to_string_lossy()).await?);
+        }
+    }
+    Ok(parts.join(";"))
+}
+
+// Overlays to composite for this render (empty when no render options were given)
+fn overlays_of(render_options: Option<&ClipRenderOptions>) -> &[OverlaySpec] {
+    render_options.map(|options| options.overlays.as_slice()).unwrap_or(&[])
+}
+
+// Speech enhancement chain for a build, when enabled
+fn enhancement_filter_of(render_options: Option<&ClipRenderOptions>) -> Option<String> {
+    let settings = render_options.and_then(|options| options.speech_enhancement.as_ref())?;
+    speech_enhancement_filter(settings)
+}
+
+// Audio filter for a source range: speech enhancement first, then censoring (so the bleep tone
+// isn't run through the denoiser)
+fn audio_filter_of(
+    enhancement: Option<&str>,
+    render_options: Option<&ClipRenderOptions>,
+    intervals: &[(f64, f64)],
+    start: f64,
+    end: f64
+) -> Option<String> {
+    let censor = render_options
+        .and_then(|options| options.censor.as_ref())
+        .and_then(|options| audio_censor_filter(intervals, options, start, end));
+    match (enhancement, censor) {
+        (Some(enhancement), Some(censor)) => Some(format!("{},{}", enhancement, censor)),
+        (enhancement, censor) => censor.or_else(|| enhancement.map(str::to_string)),
+    }
+}
+
+// Cache key input for the overlays: each file's fingerprint plus its placement/timing settings
+async fn overlay_fingerprint(overlays: &[OverlaySpec]) -> Result<String, String> {
+    if overlays.is_empty() {
+        return Ok("none".to_string());
+    }
+    let mut parts = Vec::new();
+    for overlay in overlays {
+        let settings = serde_json::to_string(overlay)
+            .map_err(|e| format!("Failed to serialize overlay settings: {}", e))?;
+        parts.push(format!("{}|{}", source_fingerprint(&overlay.path).await?, settings));
+    }
+    Ok(parts.join(";"))
+}
+
+// Video filter arguments: a plain -vf chain, or a filter_complex when overlays are composited.
+// Overlay inputs must already be on the command line right after the main input.
+fn video_filter_args(
+    pre_filter: &str,
+    post_filter: Option<&str>,
+    overlays: &[OverlaySpec],
+    aspect_ratio: &AspectRatio,
+    output_width: u32,
+    output_height: u32
+) -> Vec<String> {
+    if overlays.is_empty() {
+        let chain: Vec<&str> = std::iter::once(pre_filter)
+            .chain(post_filter)
+            .filter(|part| !part.is_empty())
+            .collect();
+        return vec!["-vf".to_string(), chain.join(",")];
+    }
+
+    let ratio_key = format!("{}:{}", aspect_ratio.width, aspect_ratio.height);
+    vec![
+        "-filter_complex".to_string(),
+        build_overlay_filter_graph(pre_filter, post_filter, overlays, &ratio_key, output_width, output_height),
+        "-map".to_string(), "[vout]".to_string(),
+        "-map".to_string(), "0:a?".to_string(),
+    ]
+}
+
+// Name of a cached artifact (its key), used to chain stage keys together
+fn artifact_name(path: &std::path::Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn output_extension(output_path: &std::path::Path) -> String {
+    output_path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "mp4".to_string())
+}
+
+// Build the ass filter with the fonts directory, escaping paths for the filter syntax
+fn ass_filter(subtitle_path: &std::path::Path, fonts_dir: Option<&std::path::Path>) -> String {
+    let sub_arg = subtitle_path.to_string_lossy().replace("\\", "/").replace(":", "\\:");
+    if let Some(fdir) = fonts_dir {
+        let fonts_dir_str = fdir.to_string_lossy().replace("\\", "/").replace(":", "\\:");
+        format!("ass='{}':fontsdir='{}'", sub_arg, fonts_dir_str)
+    } else {
+        format!("ass='{}'", sub_arg)
+    }
+}
+
+// Subtitle codec for a soft subtitle track in the given container
+fn soft_subtitle_codec(video_path: &std::path::Path) -> &'static str {
+    match video_path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("webm") => "webvtt",
+        Some("mkv") => "srt",
+        _ => "mov_text",
+    }
+}
+
+// Add caption files to a built clip as soft subtitle tracks, tagged with their language
+// (streams are copied, not re-encoded; the first track is the default)
+pub async fn mux_soft_subtitles(
+    app: &tauri::AppHandle,
+    video_path: &std::path::Path,
+    tracks: &[(std::path::PathBuf, Option<String>)]
+) -> Result<(), String> {
+    let extension = video_path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let muxed_path = video_path.with_extension(format!("subs.{}", extension));
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(), video_path.to_string_lossy().to_string(),
+    ];
+    for (captions_path, _) in tracks {
+        args.extend(["-i".to_string(), captions_path.to_string_lossy().to_string()]);
+    }
+    args.extend(["-map".to_string(), "0".to_string()]);
+    for input_idx in 1..=tracks.len() {
+        args.extend(["-map".to_string(), format!("{}:0", input_idx)]);
+    }
+    args.extend([
+        "-c".to_string(), "copy".to_string(),
+        "-c:s".to_string(), soft_subtitle_codec(video_path).to_string(),
+        "-disposition:s:0".to_string(), "default".to_string(),
+    ]);
+    for (track_idx, (_, language)) in tracks.iter().enumerate() {
+        if let Some(language) = language {
+            args.extend([format!("-metadata:s:s:{}", track_idx), format!("language={}", language)]);
+        }
+    }
+    args.push(muxed_path.to_string_lossy().to_string());
+
+    if let Err(e) = run_ffmpeg(app, args, 1, false, "Soft subtitle mux").await {
+        let _ = std::fs::remove_file(&muxed_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&muxed_path, video_path)
+        .map_err(|e| format!("Failed to replace clip with subtitled version: {}", e))
+}
+
+// Render one frame of the source at source_time, cropped and with the subtitle files burned in.
+// The frame is stamped with clip_time so the ASS events (on the clip timeline) line up with the build.
+pub async fn render_subtitle_frame(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    source_time: f64,
+    clip_time: f64,
+    aspect_ratio: &AspectRatio,
+    subtitle_paths: &[std::path::PathBuf],
+    output_path: &std::path::Path
+) -> Result<(), String> {
+    let video_info = get_video_info(app, video_path).await?;
+    let (crop_w, crop_h, crop_x, crop_y) = calculate_crop_params(video_info.width, video_info.height, aspect_ratio);
+    let fonts_dir = get_fonts_dir(app).ok();
+
+    // Same crop and RGB conversion as the build, so the frame matches the export
+    let mut filters = vec![
+        format!("crop={}:{}:{}:{}", crop_w, crop_h, crop_x, crop_y),
+        "format=rgb24".to_string(),
+        format!("setpts=PTS-STARTPTS+{:.3}/TB", clip_time),
+    ];
+    for path in subtitle_paths {
+        filters.push(ass_filter(path, fonts_dir.as_deref()));
+    }
+
+    let args = vec![
+        "-ss".to_string(), format!("{:.3}", source_time),
+        "-i".to_string(), video_path.to_string(),
+        "-frames:v".to_string(), "1".to_string(),
+        "-vf".to_string(), filters.join(","),
+        "-an".to_string(),
+        "-y".to_string(),
+        output_path.to_string_lossy().to_string(),
+    ];
+
+    run_ffmpeg(app, args, 1, !subtitle_paths.is_empty(), "Subtitle preview frame").await
+}
+
+// Render a range of the source audio to a WAV file, optionally through an audio filter
+// (speech enhancement preview)
+pub async fn render_audio_sample(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    start: f64,
+    duration: f64,
+    audio_filter: Option<&str>,
+    output_path: &std::path::Path
+) -> Result<(), String> {
+    let mut args = vec![
+        "-ss".to_string(), format!("{:.3}", start.max(0.0)),
+        "-t".to_string(), format!("{:.3}", duration),
+        "-i".to_string(), video_path.to_string(),
+        "-vn".to_string(),
+    ];
+    if let Some(filter) = audio_filter {
+        args.extend_from_slice(&["-af".to_string(), filter.to_string()]);
+    }
     args.extend_from_slice(&[
-        "-r".to_string(), frame_rate.to_string(),
-        "-c:a".to_string(), "aac".to_string(),
-        "-b:a".to_string(), "192k".to_string(),
-        "-pix_fmt".to_string(), "yuv420p".to_string(),
-        "-avoid_negative_ts".to_string(), "1".to_string(),
+        "-c:a".to_string(), "pcm_s16le".to_string(),
         "-y".to_string(),
         output_path.to_string_lossy().to_string(),
     ]);
 
-    // Process the intro/outro
-    let output = shell.sidecar("ffmpeg")
-        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
+    run_ffmpeg(app, args, 1, false, "Audio sample").await
+}
+
+// Decode a range of the source audio to mono 16-bit samples (for word alignment)
+pub async fn read_audio_samples(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    start: f64,
+    duration: f64,
+    sample_rate: u32
+) -> Result<Vec<i16>, String> {
+    let args = vec![
+        "-ss".to_string(), format!("{:.3}", start.max(0.0)),
+        "-t".to_string(), format!("{:.3}", duration),
+        "-i".to_string(), video_path.to_string(),
+        "-vn".to_string(),
+        "-ac".to_string(), "1".to_string(),
+        "-ar".to_string(), sample_rate.to_string(),
+        "-f".to_string(), "s16le".to_string(),
+        "pipe:1".to_string(),
+    ];
+
+    let command = app.shell().sidecar("ffmpeg")
+        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?;
+    let slots = acquire_ffmpeg_slots(1).await;
+    let output = command
         .args(args)
         .output()
         .await
-        .map_err(|e| format!("Failed to process {}: {}", file_prefix, e))?;
+        .map_err(|e| format!("Failed to run audio decode: {}", e))?;
+    drop(slots);
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg failed to process {}: {}", file_prefix, stderr));
+        return Err(format!("Audio decode failed: {}", stderr));
     }
+    Ok(output.stdout.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect())
+}
 
-    println!("[Rust] Successfully processed {} to: {}", file_prefix, output_path.display());
-    
-    // Cache the result (lock only for the insertion)
-    {
-        let mut cache_lock = cache.lock().unwrap();
-        cache_lock.insert(cache_key, output_path.clone());
+// Run an FFmpeg job against the global CPU budget
+async fn run_ffmpeg(
+    app: &tauri::AppHandle,
+    args: Vec<String>,
+    cost: u32,
+    uses_fonts: bool,
+    description: &str
+) -> Result<(), String> {
+    let shell = app.shell();
+    let mut command = shell.sidecar("ffmpeg")
+        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?;
+
+    if uses_fonts {
+        // Set fontconfig path for FFmpeg to find our custom fonts
+        let fontconfig_path = crate::storage::init_storage_dirs()
+            .map_err(|e| format!("Failed to get storage paths: {}", e))?
+            .temp.join("fonts.conf");
+        command = command.env("FONTCONFIG_FILE", fontconfig_path.to_string_lossy().to_string());
     }
-    
-    Ok(output_path)
-}
 
+    let slots = acquire_ffmpeg_slots(cost).await;
+    let output = command
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", description, e))?;
+    drop(slots);
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", description, stderr));
+    }
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ mod ffmpeg_utils;
 mod auth;
 mod downloads;
 mod audio;
+mod local_transcription;
 mod clips;
 mod video_server;
 mod assets;
@@ -222,6 +223,12 @@ pub fn run() {
                             sql: include_str!("../migrations/033_add_monitored_streamer_thumbnails.sql"),
                             kind: tauri_plugin_sql::MigrationKind::Up,
                         },
+                        tauri_plugin_sql::Migration {
+                            version: 34,
+                            description: "migrate_legacy_animation_styles",
+                            sql: include_str!("../migrations/034_migrate_legacy_animation_styles.sql"),
+                            kind: tauri_plugin_sql::MigrationKind::Up,
+                        },
                     ],
                 )
                 .build(),
@@ -238,6 +245,9 @@ pub fn run() {
                 eprintln!("[Rust] Warning: Failed to initialize storage directories: {}", e);
             }
 
+            // Extracted audio the frontend never released (crash, abandoned upload)
+            audio::cleanup_stale_audio_files();
+
             // Start video streaming server in Tauri's async runtime
             let _app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -327,6 +337,11 @@ pub fn run() {
             // Audio commands
             audio::extract_audio_from_video,
             audio::extract_and_chunk_audio,
+            audio::release_audio_files,
+            local_transcription::transcribe_locally,
+            local_transcription::cancel_local_transcription,
+            local_transcription::list_transcription_models,
+            local_transcription::download_transcription_model,
 
             // Waveform commands
             waveform::extract_audio_waveform,
@@ -349,6 +364,21 @@ pub fn run() {
 
             // Clips commands
             clips::build_clip_from_segments,
+            clips::build_clips_batch,
+            clips::read_clip_manifest,
+            clips::export_clip_captions,
+            clips::render_subtitle_preview,
+            clips::list_subtitle_fonts,
+            clips::install_subtitle_font,
+            clips::check_subtitle_fonts,
+            clips::import_subtitle_file,
+            clips::export_subtitle_preset,
+            clips::import_subtitle_preset,
+            clips::list_speech_enhancement_presets,
+            clips::render_speech_enhancement_preview,
+            clips::get_artifact_cache_stats,
+            clips::set_artifact_cache_limits,
+            clips::clear_artifact_cache,
             clips::cancel_clip_build,
             clips::is_clip_build_active,
 
@@ -0,0 +1,521 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+use crate::clips::{SpeechEnhancement, WhisperSegment};
+use crate::storage;
+
+// Used by the whisper backend only; without it only the model management commands are functional
+#[cfg(feature = "local-transcription")]
+use tauri_plugin_shell::ShellExt;
+#[cfg(feature = "local-transcription")]
+use crate::audio::{pick_boundary, MIN_SILENCE_SECS};
+#[cfg(feature = "local-transcription")]
+use crate::clips::WordInfo;
+#[cfg(feature = "local-transcription")]
+use crate::clips::alignment::SpeechActivity;
+#[cfg(feature = "local-transcription")]
+use crate::clips::speech_enhancement::speech_enhancement_filter;
+#[cfg(feature = "local-transcription")]
+use crate::ffmpeg_utils::get_video_duration_sync;
+
+// Offline transcription with whisper.cpp (linked through whisper-rs) on the CPU. Audio is decoded
+// by the FFmpeg sidecar in windows, each window is transcribed with token timestamps and its
+// segments are emitted as soon as they are ready, so the transcript fills in while it runs.
+// Windows end in a pause so no word is split between two of them.
+
+// Sample rate whisper expects
+#[cfg(feature = "local-transcription")]
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+// Audio decoded and transcribed per pass (keeps memory flat on multi-hour streams)
+#[cfg(feature = "local-transcription")]
+const WINDOW_SECS: f64 = 600.0;
+// A window ends in the pause closest to WINDOW_SECS within this distance
+#[cfg(feature = "local-transcription")]
+const CUT_SEARCH_SECS: f64 = 20.0;
+// Windows cut mid-speech (no pause found) overlap by this much; segments are assigned to the
+// window they start in, split at the middle of the overlap
+#[cfg(feature = "local-transcription")]
+const WINDOW_OVERLAP_SECS: f64 = 10.0;
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+// Projects with a running local transcription (false = cancel requested)
+static ACTIVE_TRANSCRIPTIONS: Lazy<Arc<Mutex<HashMap<String, bool>>>> = Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+#[cfg(feature = "local-transcription")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalTranscriptionProgress {
+    pub project_id: String,
+    pub progress: f64,
+    // Segments finished since the previous event
+    pub segments: Vec<WhisperSegment>,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDownloadProgress {
+    pub model: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+// A whisper token with its times (centiseconds from the window start) and probability
+#[cfg(feature = "local-transcription")]
+pub struct TimedToken {
+    pub text: String,
+    pub t0: i64,
+    pub t1: i64,
+    pub p: f32,
+}
+
+// Model names as published for whisper.cpp ("base.en", "small", "large-v3-turbo")
+fn validate_model_name(model: &str) -> Result<(), String> {
+    let valid = !model.is_empty()
+        && model.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        && !model.contains("..");
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid transcription model name: {}", model))
+    }
+}
+
+fn models_dir() -> Result<std::path::PathBuf, String> {
+    let paths = storage::init_storage_dirs()
+        .map_err(|e| format!("Failed to get storage paths: {}", e))?;
+    let dir = paths.assets.join("models");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create models directory: {}", e))?;
+    Ok(dir)
+}
+
+fn model_path(model: &str) -> Result<std::path::PathBuf, String> {
+    validate_model_name(model)?;
+    Ok(models_dir()?.join(format!("ggml-{}.bin", model)))
+}
+
+// Join whisper tokens into words: a token starting with a space begins a new word, special
+// tokens ("[_BEG_]", "<|endoftext|>") are dropped. Word confidence is the mean token probability.
+#[cfg(feature = "local-transcription")]
+pub fn words_from_tokens(tokens: &[TimedToken], offset: f64) -> Vec<WordInfo> {
+    let mut words: Vec<(String, i64, i64, Vec<f32>)> = Vec::new();
+    for token in tokens {
+        if token.text.starts_with("[_") || token.text.starts_with("<|") {
+            continue;
+        }
+        let starts_word = token.text.starts_with(' ') || words.is_empty();
+        let text = token.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match words.last_mut() {
+            Some((word, _, t1, probabilities)) if !starts_word => {
+                word.push_str(text);
+                *t1 = token.t1.max(*t1);
+                probabilities.push(token.p);
+            },
+            _ => words.push((text.to_string(), token.t0, token.t1, vec![token.p])),
+        }
+    }
+    words
+        .into_iter()
+        .map(|(word, t0, t1, probabilities)| WordInfo {
+            word,
+            start: offset + t0 as f64 / 100.0,
+            end: offset + t1.max(t0) as f64 / 100.0,
+            confidence: Some((probabilities.iter().sum::<f32>() / probabilities.len() as f32) as f64),
+            speaker: None,
+        })
+        .collect()
+}
+
+// End of a window decoded from `start`: the middle of the pause closest to `target`, or None when
+// the audio around it has no pause (continuous speech, music)
+#[cfg(feature = "local-transcription")]
+pub fn window_cut(samples: &[f32], start: f64, target: f64) -> Option<f64> {
+    let sample_at = |time: f64| (((time - start) * WHISPER_SAMPLE_RATE as f64).max(0.0) as usize).min(samples.len());
+    let search_start = (target - CUT_SEARCH_SECS).max(start);
+    let pcm: Vec<i16> = samples[sample_at(search_start)..sample_at(target + CUT_SEARCH_SECS)]
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+        .collect();
+    SpeechActivity::from_samples(&pcm, WHISPER_SAMPLE_RATE, search_start)
+        .and_then(|activity| pick_boundary(&activity.silences(MIN_SILENCE_SECS), target))
+}
+
+// Decode a window of the source audio to mono 16 kHz float samples
+#[cfg(feature = "local-transcription")]
+async fn decode_window(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    start: f64,
+    duration: f64,
+    audio_filter: Option<&str>
+) -> Result<Vec<f32>, String> {
+    let mut args = vec![
+        "-ss".to_string(), format!("{:.3}", start),
+        "-t".to_string(), format!("{:.3}", duration),
+        "-i".to_string(), video_path.to_string(),
+        "-vn".to_string(),
+    ];
+    // Speech enhancement runs at the source rate, before the downmix for whisper
+    if let Some(filter) = audio_filter {
+        args.extend_from_slice(&["-af".to_string(), filter.to_string()]);
+    }
+    args.extend_from_slice(&[
+        "-ac".to_string(), "1".to_string(),
+        "-ar".to_string(), WHISPER_SAMPLE_RATE.to_string(),
+        "-f".to_string(), "f32le".to_string(),
+        "pipe:1".to_string(),
+    ]);
+
+    let output = app.shell().sidecar("ffmpeg")
+        .map_err(|e| format!("Failed to get ffmpeg sidecar: {}", e))?
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg for audio window: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg audio decode failed: {}", stderr));
+    }
+    Ok(output.stdout.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
+
+// Called by whisper between compute steps with a pointer to the project id; true stops the run.
+// (set_abort_callback_safe reads its boxed closure back as the wrong type, so the raw callback is used.)
+#[cfg(feature = "local-transcription")]
+unsafe extern "C" fn abort_if_cancelled(user_data: *mut std::ffi::c_void) -> bool {
+    is_cancelled(*(user_data as *const &str))
+}
+
+#[cfg(feature = "local-transcription")]
+fn transcribe_window(
+    context: &whisper_rs::WhisperContext,
+    samples: &[f32],
+    offset: f64,
+    language: Option<&str>,
+    project_id: &str
+) -> Result<Vec<WhisperSegment>, String> {
+    use whisper_rs::{FullParams, SamplingStrategy};
+
+    let mut state = context.create_state()
+        .map_err(|e| format!("Failed to create whisper state: {}", e))?;
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    params.set_n_threads(threads as i32);
+    params.set_language(Some(language.unwrap_or("auto")));
+    params.set_token_timestamps(true);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    // Cancelling stops the window in progress instead of waiting for it to finish
+    let mut cancel_key: &str = project_id;
+    unsafe {
+        params.set_abort_callback(Some(abort_if_cancelled));
+        params.set_abort_callback_user_data(&mut cancel_key as *mut &str as *mut std::ffi::c_void);
+    }
+
+    let result = state.full(params, samples);
+    if is_cancelled(project_id) {
+        return Err("Transcription cancelled".to_string());
+    }
+    result.map_err(|e| format!("Failed to run whisper: {}", e))?;
+
+    let segment_count = state.full_n_segments()
+        .map_err(|e| format!("Failed to read whisper segments: {}", e))?;
+    let mut segments = Vec::new();
+    for i in 0..segment_count {
+        let read = || -> Result<WhisperSegment, whisper_rs::WhisperError> {
+            let token_count = state.full_n_tokens(i)?;
+            let mut tokens = Vec::new();
+            for j in 0..token_count {
+                let data = state.full_get_token_data(i, j)?;
+                tokens.push(TimedToken { text: state.full_get_token_text(i, j)?, t0: data.t0, t1: data.t1, p: data.p });
+            }
+            Ok(WhisperSegment {
+                id: i as i64,
+                start: offset + state.full_get_segment_t0(i)? as f64 / 100.0,
+                end: offset + state.full_get_segment_t1(i)? as f64 / 100.0,
+                text: state.full_get_segment_text(i)?.trim().to_string(),
+                words: Some(words_from_tokens(&tokens, offset)),
+                speaker: None,
+            })
+        };
+        let segment = read().map_err(|e| format!("Failed to read whisper segment {}: {}", i, e))?;
+        if !segment.text.is_empty() {
+            segments.push(segment);
+        }
+    }
+    Ok(segments)
+}
+
+#[cfg(feature = "local-transcription")]
+fn load_model(path: &std::path::Path) -> Result<whisper_rs::WhisperContext, String> {
+    whisper_rs::WhisperContext::new_with_params(
+        path.to_str().ok_or("Invalid model path")?,
+        whisper_rs::WhisperContextParameters::default()
+    ).map_err(|e| format!("Failed to load whisper model: {}", e))
+}
+
+#[cfg(feature = "local-transcription")]
+fn is_cancelled(project_id: &str) -> bool {
+    !ACTIVE_TRANSCRIPTIONS.lock().unwrap().get(project_id).copied().unwrap_or(false)
+}
+
+#[cfg(feature = "local-transcription")]
+async fn run_local_transcription(
+    app: &tauri::AppHandle,
+    video_path: &str,
+    project_id: &str,
+    model: &str,
+    language: Option<String>,
+    speech_enhancement: Option<SpeechEnhancement>
+) -> Result<Vec<WhisperSegment>, String> {
+    let path = model_path(model)?;
+    if !path.exists() {
+        return Err(format!("Transcription model '{}' is not downloaded", model));
+    }
+    let duration = get_video_duration_sync(app, video_path).await?;
+    println!("[Rust] Local transcription of {:.0}s with model {}", duration, model);
+    let audio_filter = speech_enhancement.as_ref().and_then(speech_enhancement_filter);
+
+    let context = Arc::new(tokio::task::spawn_blocking(move || load_model(&path))
+        .await
+        .map_err(|e| format!("Failed to load whisper model: {}", e))??);
+
+    let mut all_segments: Vec<WhisperSegment> = Vec::new();
+    let mut window_start = 0.0;
+    // Segments starting before this were kept by the previous window
+    let mut keep_from = 0.0;
+    while window_start < duration {
+        if is_cancelled(project_id) {
+            return Err("Transcription cancelled".to_string());
+        }
+        // Decode past the target so the cut can land in a pause after it
+        let target = window_start + WINDOW_SECS;
+        let last_window = target + CUT_SEARCH_SECS >= duration;
+        let decode_duration = if last_window { duration - window_start } else { WINDOW_SECS + CUT_SEARCH_SECS };
+        let mut samples = decode_window(app, video_path, window_start, decode_duration, audio_filter.as_deref()).await?;
+
+        // (end of this window, start of the next, segments starting from here go to the next)
+        let (window_end, next_start, keep_until) = if last_window {
+            (duration, duration, f64::INFINITY)
+        } else {
+            match window_cut(&samples, window_start, target) {
+                Some(cut) => (cut, cut, cut),
+                None => {
+                    println!("[Rust] No pause near {:.0}s, overlapping the next window", target);
+                    (target, target - WINDOW_OVERLAP_SECS, target - WINDOW_OVERLAP_SECS / 2.0)
+                }
+            }
+        };
+        samples.truncate(((window_end - window_start) * WHISPER_SAMPLE_RATE as f64) as usize);
+
+        let context = context.clone();
+        let language = language.clone();
+        let offset = window_start;
+        let cancel_id = project_id.to_string();
+        let mut segments = tokio::task::spawn_blocking(move || {
+            transcribe_window(&context, &samples, offset, language.as_deref(), &cancel_id)
+        }).await.map_err(|e| format!("Failed to run whisper: {}", e))??;
+        segments.retain(|segment| segment.start >= keep_from && segment.start < keep_until);
+        for (i, segment) in segments.iter_mut().enumerate() {
+            segment.id = (all_segments.len() + i) as i64;
+        }
+
+        keep_from = keep_until;
+        window_start = next_start;
+        println!("[Rust] Transcribed {:.0}/{:.0}s ({} segments)", window_end, duration, segments.len());
+        let _ = app.emit("local-transcription-progress", LocalTranscriptionProgress {
+            project_id: project_id.to_string(),
+            progress: (window_end / duration * 100.0).min(100.0),
+            segments: segments.clone(),
+            done: window_start >= duration,
+        });
+        all_segments.extend(segments);
+    }
+    Ok(all_segments)
+}
+
+#[cfg(not(feature = "local-transcription"))]
+async fn run_local_transcription(
+    _app: &tauri::AppHandle,
+    _video_path: &str,
+    _project_id: &str,
+    _model: &str,
+    _language: Option<String>,
+    _speech_enhancement: Option<SpeechEnhancement>
+) -> Result<Vec<WhisperSegment>, String> {
+    Err("This build does not include local transcription (enable the local-transcription feature)".to_string())
+}
+
+// Transcribe a video on this machine. Segments are also emitted as "local-transcription-progress"
+// events while the transcription runs.
+#[tauri::command]
+pub async fn transcribe_locally(
+    app: tauri::AppHandle,
+    video_path: String,
+    project_id: String,
+    model: String,
+    language: Option<String>,
+    speech_enhancement: Option<SpeechEnhancement>
+) -> Result<Vec<WhisperSegment>, String> {
+    {
+        let mut active = ACTIVE_TRANSCRIPTIONS.lock().unwrap();
+        if active.get(&project_id).copied().unwrap_or(false) {
+            return Err("A transcription is already running for this project".to_string());
+        }
+        active.insert(project_id.clone(), true);
+    }
+    let result = run_local_transcription(&app, &video_path, &project_id, &model, language, speech_enhancement).await;
+    ACTIVE_TRANSCRIPTIONS.lock().unwrap().remove(&project_id);
+    result
+}
+
+#[tauri::command]
+pub async fn cancel_local_transcription(project_id: String) -> Result<(), String> {
+    if let Some(active) = ACTIVE_TRANSCRIPTIONS.lock().unwrap().get_mut(&project_id) {
+        *active = false;
+    }
+    Ok(())
+}
+
+// Models already downloaded ("base.en", ...)
+#[tauri::command]
+pub async fn list_transcription_models() -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(models_dir()?)
+        .map_err(|e| format!("Failed to read models directory: {}", e))?;
+    let mut models: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix("ggml-")?.strip_suffix(".bin").map(|m| m.to_string())
+        })
+        .collect();
+    models.sort();
+    Ok(models)
+}
+
+// Download a whisper.cpp model into storage, emitting "transcription-model-download-progress"
+#[tauri::command]
+pub async fn download_transcription_model(app: tauri::AppHandle, model: String) -> Result<String, String> {
+    let path = model_path(&model)?;
+    if path.exists() {
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    let url = format!("{}/ggml-{}.bin", MODEL_BASE_URL, model);
+    println!("[Rust] Downloading transcription model from {}", url);
+    let mut response = reqwest::get(&url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download model {}: {}", model, e))?;
+    let total = response.content_length();
+
+    // Written under a temporary name so an interrupted download is never picked up as a model
+    let partial_path = path.with_extension("bin.part");
+    let mut file = tokio::fs::File::create(&partial_path)
+        .await
+        .map_err(|e| format!("Failed to create model file: {}", e))?;
+    let mut downloaded: u64 = 0;
+    let mut last_emit = std::time::Instant::now();
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial_path).await;
+                return Err(format!("Failed to download model {}: {}", model, e));
+            }
+        };
+        if let Err(e) = tokio::io::AsyncWriteExt::write_all(&mut file, &chunk).await {
+            drop(file);
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            return Err(format!("Failed to write model file: {}", e));
+        }
+        downloaded += chunk.len() as u64;
+        if last_emit.elapsed().as_millis() >= 500 {
+            let _ = app.emit("transcription-model-download-progress", ModelDownloadProgress {
+                model: model.clone(),
+                downloaded,
+                total,
+            });
+            last_emit = std::time::Instant::now();
+        }
+    }
+    let flushed = tokio::io::AsyncWriteExt::flush(&mut file).await;
+    drop(file);
+    if let Err(e) = flushed {
+        let _ = tokio::fs::remove_file(&partial_path).await;
+        return Err(format!("Failed to write model file: {}", e));
+    }
+    if let Err(e) = std::fs::rename(&partial_path, &path) {
+        let _ = std::fs::remove_file(&partial_path);
+        return Err(format!("Failed to save model file: {}", e));
+    }
+
+    println!("[Rust] Downloaded transcription model {} ({} bytes)", model, downloaded);
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_model_name() {
+        assert!(validate_model_name("base.en").is_ok());
+        assert!(validate_model_name("large-v3-turbo").is_ok());
+        assert!(validate_model_name("../secrets").is_err());
+    }
+
+    #[cfg(feature = "local-transcription")]
+    fn token(text: &str, t0: i64, t1: i64) -> TimedToken {
+        TimedToken { text: text.to_string(), t0, t1, p: 0.5 }
+    }
+
+    #[cfg(feature = "local-transcription")]
+    #[test]
+    fn test_words_from_tokens() {
+        let tokens = vec![
+            token("[_BEG_]", 0, 0),
+            token(" Hel", 10, 30),
+            token("lo", 30, 45),
+            token(",", 45, 46),
+            token(" world", 50, 90),
+            token("<|endoftext|>", 90, 90),
+        ];
+        let words = words_from_tokens(&tokens, 600.0);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "Hello,");
+        assert!((words[0].start - 600.1).abs() < 1e-9 && (words[0].end - 600.46).abs() < 1e-9);
+        assert_eq!(words[1].word, "world");
+    }
+
+    #[cfg(feature = "local-transcription")]
+    #[test]
+    fn test_window_cut_lands_in_pause() {
+        // Speech with a 0.6s pause every 4s (582.0, 586.0, ... 598.0, 602.0), decoded from 570s
+        let rate = WHISPER_SAMPLE_RATE as f64;
+        let samples: Vec<f32> = (0..(50.0 * rate) as usize)
+            .map(|n| {
+                let t = 570.0 + n as f64 / rate;
+                let amplitude = if (t - 582.0).rem_euclid(4.0) < 0.6 { 0.001 } else { 0.25 };
+                amplitude * (t * 220.0 * std::f64::consts::TAU).sin() as f32
+            })
+            .collect();
+        let cut = window_cut(&samples, 570.0, 600.0).unwrap();
+        assert!((598.0..598.6).contains(&cut), "{}", cut);
+
+        // Continuous audio has no pause to cut in
+        let flat = vec![0.25; samples.len()];
+        assert!(window_cut(&flat, 570.0, 600.0).is_none());
+    }
+}
@@ -66,11 +66,14 @@ pub async fn start_video_server_impl() {
                 Some("mov") => "video/quicktime",
                 Some("avi") => "video/x-msvideo",
                 Some("mkv") => "video/x-matroska",
+                Some("ogg") => "audio/ogg",
                 _ => "application/octet-stream",
             };
 
-            // Always require range requests for large files to prevent memory overflow
+            // Largest range served from one request (range reads are buffered in memory)
             const LARGE_FILE_THRESHOLD: u64 = 50 * 1024 * 1024; // 50MB
+            // Read size when streaming a whole file
+            const STREAM_CHUNK_SIZE: usize = 256 * 1024;
 
             // Handle Range header for seeking support
             match range_header {
@@ -175,48 +178,50 @@ pub async fn start_video_server_impl() {
                     ).into_response());
                 }
                 None => {
-                    // No range header - only serve small files entirely
-                    if file_size <= LARGE_FILE_THRESHOLD {
-                        // Small file: serve normally
-                        let bytes = match tokio::fs::read(&file_path).await {
-                            Ok(b) => b,
-                            Err(e) => {
-                                eprintln!("Failed to read small file {}: {}", file_path.display(), e);
-                                return Ok(warp::reply::with_status(
-                                    warp::reply::json(&serde_json::json!({"error": "Cannot read file"})),
-                                    warp::http::StatusCode::INTERNAL_SERVER_ERROR
-                                ).into_response());
+                    // No range header: stream the whole file in small reads so large files
+                    // (extracted audio for upload) never sit in memory
+                    let file = match tokio::fs::File::open(&file_path).await {
+                        Ok(file) => file,
+                        Err(e) => {
+                            eprintln!("Failed to open file {}: {}", file_path.display(), e);
+                            return Ok(warp::reply::with_status(
+                                warp::reply::json(&serde_json::json!({"error": "Cannot open file"})),
+                                warp::http::StatusCode::INTERNAL_SERVER_ERROR
+                            ).into_response());
+                        }
+                    };
+
+                    let body = warp::hyper::Body::wrap_stream(async_stream::stream! {
+                        use tokio::io::AsyncReadExt;
+
+                        let mut file = file;
+                        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+                        loop {
+                            match file.read(&mut buffer).await {
+                                Ok(0) => break,
+                                Ok(n) => yield Ok::<_, std::io::Error>(buffer[..n].to_vec()),
+                                Err(e) => {
+                                    yield Err(e);
+                                    break;
+                                }
                             }
-                        };
-
-                        let response = warp::reply::with_header(
-                            warp::reply::with_header(
-                                bytes,
-                                "Content-Length",
-                                file_size.to_string()
-                            ),
-                            "Content-Type",
-                            content_type
-                        );
-
-                        let response = warp::reply::with_header(
-                            response,
-                            "Accept-Ranges",
-                            "bytes"
-                        );
-
-                        return Ok(response.into_response());
-                    } else {
-                        // Large file without range: require range request
-                        return Ok(warp::reply::with_status(
-                            warp::reply::json(&serde_json::json!({
-                                "error": "Range header required",
-                                "message": "Files larger than 50MB require range requests",
-                                "file_size": file_size,
-                                "content_type": content_type
-                            })),
-                            warp::http::StatusCode::RANGE_NOT_SATISFIABLE
-                        ).into_response());
+                        }
+                    });
+
+                    let response = warp::http::Response::builder()
+                        .header("Content-Length", file_size.to_string())
+                        .header("Content-Type", content_type)
+                        .header("Accept-Ranges", "bytes")
+                        .body(body);
+                    match response {
+                        Ok(response) => Ok(response.into_response()),
+                        Err(e) => {
+                            eprintln!("Failed to build response for {}: {}", file_path.display(), e);
+                            Ok(warp::reply::with_status(
+                                warp::reply::json(&serde_json::json!({"error": "Cannot serve file"})),
+                                warp::http::StatusCode::INTERNAL_SERVER_ERROR
+                            ).into_response())
+                        }
                     }
                 }
             }
@@ -1,6 +1,8 @@
 import { ref, computed } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { useToast } from '@/composables/useToast';
+import type { SpeechEnhancement } from '@/types';
 
 export interface AudioChunk {
   chunk_id: string;
@@ -9,8 +11,47 @@ export interface AudioChunk {
   start_time: number;
   end_time: number;
   duration: number;
-  base64_data: string;
+  url: string; // local video server URL, streamed on demand
   file_size: number;
+  // Seconds shared with the neighbouring chunks; 0 where the split was placed in a silence
+  overlap_before: number;
+  overlap_after: number;
+}
+
+// Result of extract_audio_from_video
+export interface ExtractedAudio {
+  file_path: string;
+  filename: string;
+  url: string;
+  file_size: number;
+}
+
+// Load an extracted audio file (streamed from the local server) as a File for upload
+export async function fetchAudioFile(audio: { url: string; filename: string }): Promise<File> {
+  const response = await fetch(audio.url);
+  if (!response.ok) {
+    throw new Error(`Failed to load audio file ${audio.filename}: ${response.status}`);
+  }
+  const blob = await response.blob();
+  return new File([blob], audio.filename, { type: 'audio/ogg' });
+}
+
+// Delete extracted audio files once they have been uploaded
+export async function releaseAudioFiles(filePaths: string[]): Promise<void> {
+  if (filePaths.length === 0) return;
+  try {
+    await invoke<number>('release_audio_files', { filePaths });
+  } catch (err) {
+    console.warn('[AudioChunking] Failed to release audio files:', err);
+  }
+}
+
+// Payload of the audio-chunking-progress event
+interface AudioChunkingProgressEvent {
+  project_id: string;
+  completed_chunks: number;
+  total_chunks: number;
+  progress: number;
 }
 
 export interface ChunkingProgress {
@@ -45,7 +86,7 @@ export function useAudioChunking() {
       case 'detecting_duration':
         return 'Detecting video duration...';
       case 'extracting_chunks':
-        return `Extracting chunk ${progress.value.currentChunk} of ${progress.value.totalChunks}...`;
+        return `Extracted ${progress.value.currentChunk} of ${progress.value.totalChunks} chunks...`;
       case 'encoding_chunks':
         return `Encoding chunk ${progress.value.currentChunk} of ${progress.value.totalChunks}...`;
       case 'completed':
@@ -62,7 +103,9 @@ export function useAudioChunking() {
     projectId: string,
     options: {
       chunkDurationMinutes?: number;
-      overlapSeconds?: number;
+      overlapSeconds?: number; // only used where no silence is found near a chunk boundary
+      splitAtSilence?: boolean;
+      speechEnhancement?: SpeechEnhancement; // filters applied before encoding the chunks
     } = {}
   ): Promise<{ success: boolean; chunks?: AudioChunk[]; error?: string }> {
     if (isProcessing.value) {
@@ -72,8 +115,11 @@ export function useAudioChunking() {
     const {
       chunkDurationMinutes = 30, // Default 30-minute chunks
       overlapSeconds = 30, // Default 30-second overlap
+      splitAtSilence = true, // Move boundaries into nearby pauses
+      speechEnhancement,
     } = options;
 
+    let unlistenProgress: UnlistenFn | null = null;
     try {
       isExtracting.value = true;
       error.value = null;
@@ -89,12 +135,29 @@ export function useAudioChunking() {
         message: 'Analyzing video file...',
       };
 
+      // Chunks are extracted in parallel; the backend reports each one as it finishes
+      unlistenProgress = await listen<AudioChunkingProgressEvent>('audio-chunking-progress', (event) => {
+        const payload = event.payload;
+        if (payload.project_id !== projectId) return;
+        progress.value = {
+          stage: 'extracting_chunks',
+          currentChunk: payload.completed_chunks,
+          totalChunks: payload.total_chunks,
+          currentStageProgress: payload.progress,
+          // Duration detection counts for the first 5%
+          overallProgress: 5 + payload.progress * 0.95,
+          message: `Extracted ${payload.completed_chunks} of ${payload.total_chunks} audio chunks`,
+        };
+      });
+
       // Call the Rust function
       const audioChunks = await invoke<AudioChunk[]>('extract_and_chunk_audio', {
         videoPath,
         projectId,
         chunkDurationMinutes,
         overlapSeconds,
+        splitAtSilence,
+        speechEnhancement: speechEnhancement ?? null,
       });
 
       // Update progress
@@ -134,6 +197,7 @@ export function useAudioChunking() {
 
       return { success: false, error: errorMessage };
     } finally {
+      unlistenProgress?.();
       isExtracting.value = false;
       isChunking.value = false;
     }
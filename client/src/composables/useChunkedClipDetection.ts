@@ -7,6 +7,7 @@ import {
 } from '@/services/database';
 import { useChunkedTranscriptCache } from './useChunkedTranscriptCache';
 import { useVideoOperations } from './useVideoOperations';
+import { fetchAudioFile, releaseAudioFiles, type ExtractedAudio } from './useAudioChunking';
 import { useToast } from '@/composables/useToast';
 import { useFocalPointDetection } from '@/composables/useFocalPointDetection';
 import api from '@/services/api';
@@ -312,22 +313,19 @@ export function useChunkedClipDetection() {
 
       // Generate audio file using existing FFmpeg function
       const { invoke } = await import('@tauri-apps/api/core');
-      const [filename, base64Data] = await invoke<[string, string]>('extract_audio_from_video', {
+      const audio = await invoke<ExtractedAudio>('extract_audio_from_video', {
         videoPath: projectVideo.file_path,
         outputPath: 'temp_audio_audio_only.ogg',
       });
 
-      // Convert base64 back to binary
-      const binaryString = atob(base64Data);
-      const bytes = new Uint8Array(binaryString.length);
-      for (let i = 0; i < binaryString.length; i++) {
-        bytes[i] = binaryString.charCodeAt(i);
+      // Stream the file from the local server, then drop it from disk
+      let audioFile: File;
+      try {
+        audioFile = await fetchAudioFile(audio);
+      } finally {
+        await releaseAudioFiles([audio.file_path]);
       }
 
-      // Create Blob and File
-      const blob = new Blob([bytes], { type: 'audio/ogg' });
-      const audioFile = new File([blob], filename, { type: 'audio/ogg' });
-
       progress.value = {
         stage: 'detecting_clips',
         progress: 70,
@@ -402,11 +400,12 @@ export function useChunkedClipDetection() {
       if (audioResult.chunks && audioResult.chunks.length > 0) {
         // Use the first chunk as a fallback
         const firstChunk = audioResult.chunks[0];
-        const audioBlob = new Blob([Buffer.from(firstChunk.base64_data, 'base64')], {
-          type: 'audio/ogg',
-        });
-        const audioFile = new File([audioBlob], firstChunk.filename, { type: 'audio/ogg' });
-        formData.append('audio', audioFile);
+        try {
+          const audioFile = await fetchAudioFile(firstChunk);
+          formData.append('audio', audioFile);
+        } finally {
+          await releaseAudioFiles(audioResult.chunks.map((chunk) => chunk.file_path));
+        }
       } else {
         throw new Error('No audio chunks available for fallback');
       }
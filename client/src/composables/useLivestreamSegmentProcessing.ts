@@ -16,6 +16,7 @@ import {
 } from '@/services/database';
 import type { SegmentEventPayload, SegmentJob } from '@/types/livestream';
 import type { ClipWithVersion, SubtitleSettings } from '@/services/database';
+import { fetchAudioFile, releaseAudioFiles, type ExtractedAudio } from './useAudioChunking';
 
 const DEFAULT_LIVE_PROMPT =
   'Detect the most viral, high-energy PumpFun livestream moments suitable for short-form clips.';
@@ -24,16 +25,6 @@ interface ProcessingJob extends SegmentJob {
   onProgress?: (status: string) => void;
 }
 
-function base64ToBlob(base64: string, mime = 'audio/ogg'): Blob {
-  const binary = atob(base64);
-  const length = binary.length;
-  const bytes = new Uint8Array(length);
-  for (let i = 0; i < length; i += 1) {
-    bytes[i] = binary.charCodeAt(i);
-  }
-  return new Blob([bytes], { type: mime });
-}
-
 function filenameFromPath(filePath: string): string {
   const parts = filePath.split(/[/\\]/).filter(Boolean);
   return parts[parts.length - 1] || filePath;
@@ -139,16 +130,17 @@ export function useLivestreamSegmentProcessing() {
 
       if (job.detectClips) {
         job.onProgress?.('Separating audio');
-        const [audioFilename, audioBase64] = await invoke<[string, string]>(
-          'extract_audio_from_video',
-          {
-            videoPath: job.filePath,
-            outputPath: `segment_${job.segmentId}_audio.ogg`,
-          }
-        );
+        const audio = await invoke<ExtractedAudio>('extract_audio_from_video', {
+          videoPath: job.filePath,
+          outputPath: `segment_${job.segmentId}_audio.ogg`,
+        });
 
-        const audioBlob = base64ToBlob(audioBase64);
-        const audioFile = new File([audioBlob], audioFilename, { type: 'audio/ogg' });
+        let audioFile: File;
+        try {
+          audioFile = await fetchAudioFile(audio);
+        } finally {
+          await releaseAudioFiles([audio.file_path]);
+        }
 
         const formData = new FormData();
         formData.append('project_id', segmentProjectId);
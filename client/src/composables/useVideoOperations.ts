@@ -4,6 +4,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
 import { useToast } from '@/composables/useToast';
 import { useAudioChunking, type AudioChunk } from './useAudioChunking';
+import type { SpeechEnhancement } from '@/types';
 
 export function useVideoOperations() {
   const uploading = ref(false);
@@ -162,6 +163,8 @@ export function useVideoOperations() {
     options: {
       chunkDurationMinutes?: number;
       overlapSeconds?: number;
+      splitAtSilence?: boolean;
+      speechEnhancement?: SpeechEnhancement;
     } = {}
   ): Promise<{ success: boolean; chunks?: AudioChunk[]; error?: string }> {
     try {
@@ -1,5 +1,6 @@
 import { getDatabase, timestamp, generateId } from './core';
 import type { CustomSubtitlePreset, SubtitleSettings } from './types';
+import type { SubtitleAnimationStyle } from '@/types';
 
 /**
  * Create a new custom subtitle preset
@@ -223,7 +224,7 @@ export function customPresetToSettings(preset: CustomSubtitlePreset): SubtitleSe
     position: preset.position as 'top' | 'middle' | 'bottom',
     positionPercentage: preset.position_percentage,
     maxWidth: preset.max_width,
-    animationStyle: (preset.animation_style as 'none' | 'fade' | 'word-by-word') || 'none',
+    animationStyle: (preset.animation_style as SubtitleAnimationStyle) || 'pop',
     lineHeight: preset.line_height,
     letterSpacing: preset.letter_spacing,
     textAlign: preset.text_align as 'left' | 'center' | 'right',
@@ -1,5 +1,7 @@
 // Core Database Types
 
+import type { SubtitleAnimationStyle } from '@/types';
+
 export interface Project {
   id: string;
   name: string;
@@ -298,11 +300,43 @@ export interface CustomSubtitlePreset {
   updated_at: number;
 }
 
+export interface CaptionChunking {
+  breakOnPunctuation: boolean;
+  pauseThreshold: number; // seconds
+  maxCharsPerLine: number; // 0 = no limit
+  maxLines: number; // 0 = no limit
+  minDuration: number; // seconds
+  maxDuration: number; // seconds
+}
+
+// Caption style overrides for one speaker (unset fields keep the base settings)
+export interface SpeakerStyle {
+  speaker: string;
+  textColor?: string;
+  border1Color?: string;
+  border2Color?: string;
+  backgroundColor?: string;
+  activeWordColor?: string;
+  activeBoxColor?: string;
+  positionX?: number; // percent of frame width
+}
+
+// Caption keyword emphasis / emoji rule (the first matching rule wins)
+export interface CaptionDecorationRule {
+  keywords?: string[]; // whole words, case-insensitive
+  pattern?: string; // regex matched against each word
+  color?: string;
+  scale?: number; // percent
+  uppercase?: boolean;
+  emoji?: string; // shown after the word
+}
+
 export interface SubtitleSettings {
   enabled: boolean;
   fontFamily: string;
   fontSize: number;
   fontWeight: number;
+  italic?: boolean;
   textColor: string;
   backgroundColor: string;
   backgroundEnabled: boolean;
@@ -317,7 +351,7 @@ export interface SubtitleSettings {
   position: 'top' | 'middle' | 'bottom';
   positionPercentage: number;
   maxWidth: number;
-  animationStyle: 'none' | 'fade' | 'word-by-word';
+  animationStyle: SubtitleAnimationStyle;
   lineHeight: number;
   letterSpacing: number;
   textAlign: 'left' | 'center' | 'right';
@@ -326,5 +360,15 @@ export interface SubtitleSettings {
   padding: number;
   borderRadius: number;
   wordSpacing: number;
+  // Active-word emphasis (rendered in exported clips)
+  activeWordColor?: string | null;
+  activeBoxColor?: string | null;
+  activeBoxPadding?: number;
+  activeBoxRadius?: number;
+  inactiveWordOpacity?: number;
+  // Caption paging rules (defaults applied by the exporter when omitted)
+  chunking?: CaptionChunking;
+  speakerStyles?: SpeakerStyle[];
+  decorations?: CaptionDecorationRule[];
   selectedPresetId?: string | null;
 }
@@ -4,6 +4,7 @@ export interface WordInfo {
   start: number;
   end: number;
   confidence?: number;
+  speaker?: string;
 }
 
 export interface WhisperSegment {
@@ -12,6 +13,46 @@ export interface WhisperSegment {
   end: number;
   text: string;
   words?: WordInfo[];
+  speaker?: string;
+}
+
+// "local-transcription-progress" event payload (segments finished since the previous event)
+export interface LocalTranscriptionProgress {
+  projectId: string;
+  progress: number; // 0-100
+  segments: WhisperSegment[];
+  done: boolean;
+}
+
+// Result of import_subtitle_file (SRT, WebVTT or ASS parsed into a transcript)
+export interface ImportedSubtitles {
+  format: 'srt' | 'vtt' | 'ass';
+  segments: WhisperSegment[];
+  words: WordInfo[];
+}
+
+// Speech enhancement filter settings (clip render options, audio extraction, local transcription)
+export interface SpeechEnhancement {
+  highpassHz?: number; // 0 = off
+  noiseReduction?: 'none' | 'fft';
+  noiseReductionDb?: number; // fft denoiser strength
+  deesser?: number; // 0-1, 0 = off
+  compressor?: boolean;
+}
+
+// Named preset from list_speech_enhancement_presets
+export interface SpeechEnhancementPreset {
+  id: string;
+  name: string;
+  description: string;
+  settings: SpeechEnhancement;
+}
+
+// Result of render_speech_enhancement_preview: the same WAV sample with and without the filters
+export interface SpeechEnhancementPreview {
+  originalPath: string;
+  enhancedPath: string;
+  filter: string | null;
 }
 
 export interface Timestamp {
@@ -164,11 +205,62 @@ export interface TimelineVideoTrackEmits {
 }
 
 // Subtitle Types
+export interface CaptionChunking {
+  breakOnPunctuation: boolean;
+  pauseThreshold: number; // seconds
+  maxCharsPerLine: number; // 0 = no limit
+  maxLines: number; // 0 = no limit
+  minDuration: number; // seconds
+  maxDuration: number; // seconds
+}
+
+// Shareable subtitle preset file (export_subtitle_preset / import_subtitle_preset)
+export interface SubtitlePresetFile {
+  version: number;
+  name: string;
+  description?: string | null;
+  settings: SubtitleSettings;
+}
+
+// Caption style overrides for one speaker (unset fields keep the base settings)
+export interface SpeakerStyle {
+  speaker: string;
+  textColor?: string;
+  border1Color?: string;
+  border2Color?: string;
+  backgroundColor?: string;
+  activeWordColor?: string;
+  activeBoxColor?: string;
+  positionX?: number; // percent of frame width
+}
+
+// Caption keyword emphasis / emoji rule (the first matching rule wins)
+export interface CaptionDecorationRule {
+  keywords?: string[]; // whole words, case-insensitive
+  pattern?: string; // regex matched against each word
+  color?: string;
+  scale?: number; // percent
+  uppercase?: boolean;
+  emoji?: string; // shown after the word
+}
+
+// Caption animation ('none' = words shown without animation)
+export type SubtitleAnimationStyle =
+  | 'none'
+  | 'pop'
+  | 'karaoke'
+  | 'bounce'
+  | 'fade-in'
+  | 'typewriter'
+  | 'slide-up'
+  | 'highlight-box';
+
 export interface SubtitleSettings {
   enabled: boolean;
   fontFamily: string;
   fontSize: number;
   fontWeight: number;
+  italic?: boolean;
   textColor: string;
   backgroundColor: string;
   backgroundEnabled: boolean;
@@ -183,7 +275,7 @@ export interface SubtitleSettings {
   position: 'top' | 'middle' | 'bottom';
   positionPercentage: number;
   maxWidth: number;
-  animationStyle: 'none' | 'fade' | 'word-by-word';
+  animationStyle: SubtitleAnimationStyle;
   lineHeight: number;
   letterSpacing: number;
   textAlign: 'left' | 'center' | 'right';
@@ -192,6 +284,16 @@ export interface SubtitleSettings {
   padding: number;
   borderRadius: number;
   wordSpacing: number;
+  // Active-word emphasis (rendered in exported clips)
+  activeWordColor?: string | null;
+  activeBoxColor?: string | null;
+  activeBoxPadding?: number;
+  activeBoxRadius?: number;
+  inactiveWordOpacity?: number;
+  // Caption paging rules (defaults applied by the exporter when omitted)
+  chunking?: CaptionChunking;
+  speakerStyles?: SpeakerStyle[];
+  decorations?: CaptionDecorationRule[];
   selectedPresetId?: string | null;
 }
 